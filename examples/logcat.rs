@@ -1,4 +1,4 @@
-use srcds_log_parser::{LogMessage, MessageType};
+use srcds_log_parser::{LogMessage, MessageType, MAX_PACKET};
 
 use std::{env, net::UdpSocket};
 
@@ -13,7 +13,7 @@ fn main() {
     let sock = UdpSocket::bind(("0.0.0.0", port)).expect("Could not bind to port");
     println!("Listening on port {}", port);
 
-    let mut buf = [0u8; 1024];
+    let mut buf = [0u8; MAX_PACKET];
     loop {
         let (len, from) = sock.recv_from(&mut buf).unwrap();
         let message = match LogMessage::from_bytes(&buf[..len]) {
@@ -0,0 +1,34 @@
+use srcds_log_parser::{LineBuffer, Scoreboard};
+
+use std::{env, fs::File, io::Read, thread, time::Duration};
+
+/// Follows a log file like `tail -f`, printing the kill/death table whenever it changes.
+fn main() {
+    let path = env::args().nth(1).expect("Usage: scoreboard <log file>");
+    let mut file = File::open(&path).expect("Could not open log file");
+
+    let mut buffer = LineBuffer::new();
+    let mut scoreboard = Scoreboard::new();
+    let mut chunk = vec![];
+    loop {
+        chunk.clear();
+        file.read_to_end(&mut chunk)
+            .expect("Could not read log file");
+        if chunk.is_empty() {
+            thread::sleep(Duration::from_millis(500));
+            continue;
+        }
+
+        for line in buffer.push(&chunk) {
+            match line {
+                Ok(message) => scoreboard.push(&message.parse_message_type()),
+                Err(e) => eprintln!("Could not parse line: {e:?}"),
+            }
+        }
+
+        println!("\n{:<24} {:>6} {:>6}", "steamid", "kills", "deaths");
+        for (steamid, tally) in scoreboard.top_killers() {
+            println!("{steamid:<24} {:>6} {:>6}", tally.kills, tally.deaths);
+        }
+    }
+}
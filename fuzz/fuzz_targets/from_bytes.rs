@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use srcds_log_parser::LogMessage;
+
+fuzz_target!(|data: &[u8]| {
+    // must only ever return Ok or Err, never panic
+    if let Ok(message) = LogMessage::from_bytes(data) {
+        let _ = message.parse_message_type();
+    }
+});
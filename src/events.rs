@@ -0,0 +1,82 @@
+use crate::{MessageType, User};
+
+/// A [`MessageType::ChatMessage`], extracted with `TryFrom`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChatEvent {
+    pub from: User,
+    pub message: String,
+    /// `say_team` rather than `say`
+    pub team: bool,
+}
+
+/// A [`MessageType::Killed`], extracted with `TryFrom`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KillEvent {
+    pub attacker: User,
+    pub victim: User,
+    pub weapon: String,
+    pub assister: Option<User>,
+}
+
+/// Fails with the original message if it isn't a chat message
+impl TryFrom<MessageType> for ChatEvent {
+    type Error = MessageType;
+
+    fn try_from(message: MessageType) -> Result<Self, Self::Error> {
+        match message {
+            MessageType::ChatMessage {
+                from,
+                message,
+                team,
+            } => Ok(Self {
+                from,
+                message,
+                team,
+            }),
+            other => Err(other),
+        }
+    }
+}
+
+/// Fails with the original message if it isn't a kill
+impl TryFrom<MessageType> for KillEvent {
+    type Error = MessageType;
+
+    fn try_from(message: MessageType) -> Result<Self, Self::Error> {
+        match message {
+            MessageType::Killed {
+                attacker,
+                victim,
+                weapon,
+                assister,
+            } => Ok(Self {
+                attacker,
+                victim,
+                weapon,
+                assister,
+            }),
+            other => Err(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_and_kill_projections() {
+        let chat = MessageType::from_message(r#""A<1><[U:1:1]><Red>" say_team "push""#);
+        let event = ChatEvent::try_from(chat).unwrap();
+        assert!(event.from.name == "A" && event.message == "push" && event.team);
+
+        let kill = MessageType::from_message(
+            r#""A<1><[U:1:1]><Red>" killed "B<2><[U:1:2]><Blue>" with "scattergun""#,
+        );
+        let event: KillEvent = kill.clone().try_into().unwrap();
+        assert!(event.victim.name == "B" && event.weapon == "scattergun");
+
+        let not_chat = ChatEvent::try_from(kill.clone());
+        assert!(not_chat == Err(kill));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{LogMessage, MessageType, SecretKind};
+use chrono::NaiveDateTime;
+use std::io::{self, Write};
+
+#[derive(serde::Serialize)]
+struct JsonLine<'a> {
+    timestamp: &'a NaiveDateTime,
+    secret: &'a SecretKind,
+    message_type: &'a MessageType,
+}
+
+/// Writes each event as a JSON object on its own line, for shipping to log stores like ELK.
+///
+/// Each object holds the message's `timestamp` and `secret` alongside the externally tagged
+/// `message_type`. The writer is flushed once every event has been written.
+pub fn write_jsonl<W, I>(mut w: W, events: I) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = (LogMessage, MessageType)>,
+{
+    for (message, message_type) in events {
+        let line = JsonLine {
+            timestamp: &message.timestamp,
+            secret: &message.secret,
+            message_type: &message_type,
+        };
+        serde_json::to_writer(&mut w, &line)?;
+        w.write_all(b"\n")?;
+    }
+    w.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_many;
+
+    #[test]
+    fn two_events() {
+        const SNIPPET: &str = "SmeowL 02/09/2024 - 08:00:50: Log file closed\n\
+            L 02/09/2024 - 08:00:51: \"A<1><[U:1:1]><Red>\" say \"hi\"";
+        let mut out = vec![];
+        write_jsonl(&mut out, parse_many(SNIPPET).into_iter()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let expected = [
+            r#"{"timestamp":"2024-02-09T08:00:50","secret":{"Udp":"meow"},"message_type":"LogFileClosed"}"#,
+            r#"{"timestamp":"2024-02-09T08:00:51","secret":"None","message_type":{"ChatMessage":{"from":{"name":"A","uid":1,"steamid":"[U:1:1]","team":"Red"},"message":"hi","team":false}}}"#,
+        ];
+        assert!(out.lines().collect::<Vec<_>>() == expected, "{out}");
+        assert!(out.ends_with('\n'));
+    }
+}
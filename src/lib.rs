@@ -1,3 +1,26 @@
+mod events;
+#[cfg(feature = "json")]
+mod jsonl;
+mod line_buffer;
 mod parser;
+mod reconnect;
+mod rounds;
+mod stats;
+mod visitor;
+mod window;
 
-pub use parser::{LogMessage, LogParseError, MessageType, User};
+pub use events::{ChatEvent, KillEvent};
+#[cfg(feature = "json")]
+pub use jsonl::write_jsonl;
+pub use line_buffer::LineBuffer;
+pub use parser::{
+    is_plausible_for, parse_many, Accolade, Angles, BackendEvent, BombAction, BuildingType,
+    CritKind, FlagEventKind, Game, LazyLogMessage, LocalDateTime, LogMessage, LogParseError,
+    MessageKind, MessageType, MvmWaveEvent, ObjectMovement, ParserConfig, Position, Properties,
+    RconSource, SecretKind, Team, TfClass, User, MAX_LOG_LINE, MAX_PACKET,
+};
+pub use reconnect::{RapidReconnect, ReconnectDetector};
+pub use rounds::{Round, RoundSplitter};
+pub use stats::{map_rotation, scoreboard, sessions, PlayerTally, Scoreboard, Session};
+pub use visitor::MessageVisitor;
+pub use window::{events_between, index_by_time, TimeWindow};
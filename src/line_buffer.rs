@@ -0,0 +1,57 @@
+use crate::{LogMessage, LogParseError};
+
+/// Splits chunks of a log file, as they are read or appended to, into [`LogMessage`]s.
+///
+/// A trailing partial line is held back until the rest of it arrives.
+#[derive(Debug, Clone, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a chunk, returning every line it completed. Blank lines are skipped.
+    pub fn push(&mut self, data: &[u8]) -> Vec<Result<LogMessage, LogParseError>> {
+        self.pending.extend_from_slice(data);
+        let Some(end) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return vec![];
+        };
+        let lines: Vec<u8> = self.pending.drain(..=end).collect();
+        lines
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(LogMessage::from_bytes)
+            .collect()
+    }
+
+    /// The partial line still waiting for its newline
+    pub fn pending(&self) -> &[u8] {
+        &self.pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_back_partial_lines() {
+        let mut buffer = LineBuffer::new();
+        assert!(buffer
+            .push(b"L 02/09/2024 - 08:00:50: Log file ")
+            .is_empty());
+
+        let lines = buffer.push(b"closed\r\n\nL 02/09/2024 - 08:00:51: Server cvars");
+        assert!(lines.len() == 1);
+        assert!(lines[0].as_ref().unwrap().message == "Log file closed");
+        assert!(buffer.pending() == b"L 02/09/2024 - 08:00:51: Server cvars");
+
+        let lines = buffer.push(b" start\n");
+        assert!(lines[0].as_ref().unwrap().message == "Server cvars start");
+        assert!(buffer.pending().is_empty());
+    }
+}
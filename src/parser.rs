@@ -1,14 +1,40 @@
-use chrono::{self, NaiveDateTime};
-use std::{fmt, str::FromStr};
+use chrono::{self, Datelike, NaiveDateTime, Timelike};
+use std::{fmt, net::Ipv4Addr, str::FromStr};
 
 mod message_type;
-pub use message_type::{MessageType, User};
+pub use message_type::{
+    is_plausible_for, Accolade, Angles, BackendEvent, BombAction, BuildingType, CritKind,
+    FlagEventKind, Game, MessageKind, MessageType, MvmWaveEvent, ObjectMovement, ParserConfig,
+    Position, Properties, RconSource, Team, TfClass, User,
+};
+
+/// The longest log line a server sends, for sizing receive buffers.
+///
+/// The engine formats each line into a fixed 1024 byte buffer before logging it, truncating
+/// anything longer, so a line never exceeds this.
+pub const MAX_LOG_LINE: usize = 1024;
+
+/// The longest secret [`MAX_PACKET`] leaves room for. `sv_logsecret` is a number, so this is
+/// a conservative bound rather than an engine limit.
+const MAX_SECRET: usize = 256;
+
+/// The largest UDP log packet to expect, for sizing receive buffers.
+///
+/// On top of a [`MAX_LOG_LINE`] line, a packet carries the four `0xFF` bytes, the `R`/`S` byte,
+/// the secret and the `L ` before the timestamp. Secrets are assumed to be at most 256 bytes,
+/// a conservative bound.
+pub const MAX_PACKET: usize = PACKET_HEADER.len() + 1 + MAX_SECRET + 2 + MAX_LOG_LINE;
 
 const PACKET_HEADER: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
 const MAGIC_NOPASSWORD_BYTE: u8 = 0x52; // R
 const MAGIC_PASSWORD_BYTE: u8 = 0x53; // S
 const MAGIC_STRING_END: u8 = 0x4C; // L
 
+/// Standard `con_timestamp` format, e.g. `02/09/2024 - 08:00:50: `
+const TIMESTAMP_FORMAT: &str = "%m/%d/%Y - %H:%M:%S: ";
+/// 12-hour format some reconfigured hosts log, e.g. `02/09/2024 - 08:00:50 PM: `
+const TIMESTAMP_FORMAT_12H: &str = "%m/%d/%Y - %I:%M:%S %p: ";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogParseError {
     TooShort,
@@ -16,6 +42,8 @@ pub enum LogParseError {
     BadPasswordByte(u8),
     NoMagicStringEnd,
     BadTimestamp,
+    /// Longer than the limit given to [`LogMessage::from_bytes_limited`]
+    TooLong,
 }
 
 impl fmt::Display for LogParseError {
@@ -26,15 +54,39 @@ impl fmt::Display for LogParseError {
 }
 impl std::error::Error for LogParseError {}
 
+/// The authentication a log line arrived with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecretKind {
+    /// Read from a file, or sent without `sv_logsecret`
+    #[default]
+    None,
+    /// The `sv_logsecret` from the `S<secret>L ` header of a UDP packet
+    Udp(String),
+    /// The token of a `logaddress_add_http` URL, see [`LogMessage::from_http_body`]
+    HttpToken(String),
+}
+
+impl SecretKind {
+    /// The secret or token, whichever transport it came from
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::None => None,
+            Self::Udp(secret) | Self::HttpToken(secret) => Some(secret),
+        }
+    }
+}
+
 /// Single log line
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogMessage {
     /// The raw timestamp at the start of the line
     pub timestamp: NaiveDateTime,
     /// The raw string message with timestamps and headers removed.
     pub message: String,
-    /// If sv_logsecret is set on the server and this log was received over UDP, this will be the received secret
-    pub secret: Option<String>,
+    /// The received sv_logsecret or HTTP token, if the server sent one
+    pub secret: SecretKind,
 }
 
 impl FromStr for LogMessage {
@@ -45,48 +97,87 @@ impl FromStr for LogMessage {
 }
 
 impl LogMessage {
+    /// Parses a single log line after removing a caller-defined prefix with `skip`, for
+    /// aggregators that prepend their own sequence number or source tag to each line
+    pub fn from_bytes_with_prefix_skip<F>(data: &[u8], skip: F) -> Result<Self, LogParseError>
+    where
+        F: FnOnce(&[u8]) -> &[u8],
+    {
+        Self::from_bytes(skip(data))
+    }
+
     /// Parses a single log line
     pub fn from_bytes(data: &[u8]) -> Result<Self, LogParseError> {
-        // parse off the header
-        let (header, rest) = match data.iter().position(|&e| e == MAGIC_STRING_END) {
-            None => return Err(LogParseError::NoMagicStringEnd),
-            Some(idx) => (&data[..idx], &data[(idx + 2)..]),
-        };
+        let (secret, rest) = split_header(data)?;
+        Self::from_timestamped(rest, secret)
+    }
 
-        let secret: Option<String> = if header.len() > 0 {
-            let mut header = header;
-            // udp packets start with four 0xFF bytes
-            if header.len() > 4 {
-                let udp_base = &header[..4];
-                if udp_base == PACKET_HEADER {
-                    // cut them off
-                    header = &header[4..];
-                }
-            }
+    /// Parses a single log line like [`LogMessage::from_bytes`], refusing anything longer than
+    /// `max_len` bytes before doing any work, to guard relays against oversized packets
+    pub fn from_bytes_limited(data: &[u8], max_len: usize) -> Result<Self, LogParseError> {
+        if data.len() > max_len {
+            return Err(LogParseError::TooLong);
+        }
+        Self::from_bytes(data)
+    }
 
-            // secret indication byte
-            let secret_byte = header[0];
-            if secret_byte == MAGIC_PASSWORD_BYTE {
-                // has secret, then grab
-                Some(String::from_utf8_lossy(&header[1..]).to_string())
-            } else if secret_byte == MAGIC_NOPASSWORD_BYTE {
-                // no secret
-                None
-            } else {
-                // there is a header, but it's not a password byte, so error
-                return Err(LogParseError::BadPasswordByte(secret_byte).into());
-            }
-        } else {
-            // no header = no secret
-            None
+    /// Parses a single log line like [`LogMessage::from_bytes`], with the timestamp in the first
+    /// of `formats` that parses, for batches mixing servers with different timestamp settings.
+    ///
+    /// Each format is a `chrono` format string that includes the `: ` ending the timestamp.
+    pub fn from_bytes_try_formats(data: &[u8], formats: &[&str]) -> Result<Self, LogParseError> {
+        let (secret, rest) = split_header(data)?;
+        let rest = String::from_utf8_lossy(rest);
+        let (timestamp, message) = formats
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_and_remainder(&rest, format).ok())
+            .ok_or(LogParseError::BadTimestamp)?;
+        Ok(Self {
+            timestamp,
+            message: message.to_owned(),
+            secret,
+        })
+    }
+
+    /// Parses a single log line like [`LogMessage::from_bytes`], but leaves the timestamp
+    /// unparsed until [`LazyLogMessage::timestamp`] is called, so lines with a corrupt
+    /// timestamp are still usable by consumers that only read the message
+    pub fn from_bytes_lazy_timestamp(data: &[u8]) -> Result<LazyLogMessage, LogParseError> {
+        let (secret, rest) = split_header(data)?;
+        let rest = String::from_utf8_lossy(rest);
+        // the timestamp ends at the first `: `, as its own colons are never followed by a space
+        let Some((raw_timestamp, message)) = rest.split_once(": ") else {
+            return Err(LogParseError::BadTimestamp);
         };
+        Ok(LazyLogMessage {
+            raw_timestamp: format!("{raw_timestamp}: "),
+            message: message.to_owned(),
+            secret,
+        })
+    }
 
+    /// Parses the lines of a `logaddress_add_http` request body, attaching the `token` from
+    /// the URL the server was configured with. The `L ` before each timestamp is optional.
+    pub fn from_http_body(body: &[u8], token: Option<&str>) -> Vec<Result<Self, LogParseError>> {
+        let secret = token.map_or(SecretKind::None, |token| {
+            SecretKind::HttpToken(token.to_owned())
+        });
+        body.split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let line = line.strip_prefix(&[MAGIC_STRING_END, b' ']).unwrap_or(line);
+                Self::from_timestamped(line, secret.clone())
+            })
+            .collect()
+    }
+
+    /// Parses the `<timestamp>: <message>` following the header
+    fn from_timestamped(data: &[u8], secret: SecretKind) -> Result<Self, LogParseError> {
         // convert rest of header to string for NaiveDateTime's parser
-        let message = String::from_utf8_lossy(rest).to_string();
+        let message = String::from_utf8_lossy(data).to_string();
         // strip timestamp
-        let (timestamp, rest) =
-            NaiveDateTime::parse_and_remainder(&message, "%m/%d/%Y - %H:%M:%S: ")
-                .map_err(|_| LogParseError::BadTimestamp)?;
+        let (timestamp, rest) = parse_timestamp(&message)?;
 
         // get message
         let message = rest[0..rest.len()].to_owned();
@@ -98,12 +189,220 @@ impl LogMessage {
         })
     }
 
+    /// Compares the timestamp and message only, ignoring transport details such as the secret.
+    ///
+    /// With both file and UDP logging enabled the same event arrives twice; unlike `==`, this
+    /// treats those copies as equal.
+    pub fn content_eq(&self, other: &Self) -> bool {
+        self.timestamp == other.timestamp && self.message == other.message
+    }
+
+    /// Whether the packet was sent with the expected `sv_logsecret` or HTTP token, for listeners
+    /// that should reject forged packets. A message without a secret never matches.
+    ///
+    /// Every byte is compared regardless of where the first mismatch is, so the time taken
+    /// doesn't reveal how much of a guessed secret was right.
+    pub fn secret_matches(&self, expected: &str) -> bool {
+        let Some(secret) = self.secret.as_str() else {
+            return false;
+        };
+        let (secret, expected) = (secret.as_bytes(), expected.as_bytes());
+        let diff = secret
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (a, b)| diff | (a ^ b));
+        diff == 0 && secret.len() == expected.len()
+    }
+
+    /// Replaces the user tags in the raw message with pseudonyms, see [`User::anonymize`].
+    ///
+    /// Only the `"name<uid><steamid><team>"` tags are rewritten; names mentioned elsewhere,
+    /// such as inside chat text, are left as is.
+    pub fn anonymize(&mut self, salt: &str) {
+        let mut message_type = self.parse_message_type();
+        for user in message_type.users_mut() {
+            let mut pseudonym = user.clone();
+            pseudonym.anonymize(salt);
+            // covers quoted and unquoted tags alike, and accolades' bare `name<uid>`
+            let tag = |user: &User| match user.steamid.as_str() {
+                "" => format!("{}<{}>", user.name, user.uid),
+                steamid => format!("{}<{}><{}>", user.name, user.uid, steamid),
+            };
+            self.message = self.message.replace(&tag(user), &tag(&pseudonym));
+        }
+    }
+
+    /// Zeroes every IPv4 address in the raw message, keeping any port, for sharing logs
+    /// alongside [`LogMessage::anonymize`]. See [`MessageType::scrub_locations`] for the parsed
+    /// form.
+    pub fn scrub_ips(&mut self) {
+        let mut scrubbed = String::with_capacity(self.message.len());
+        let mut rest = self.message.as_str();
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            let end = rest[start..]
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .map_or(rest.len(), |len| start + len);
+            let run = &rest[start..end];
+            scrubbed.push_str(&rest[..start]);
+            let preceded_by_dot = scrubbed.ends_with('.');
+            if !preceded_by_dot && run.parse::<Ipv4Addr>().is_ok() {
+                scrubbed.push_str("0.0.0.0");
+            } else {
+                scrubbed.push_str(run);
+            }
+            rest = &rest[end..];
+        }
+        scrubbed.push_str(rest);
+        self.message = scrubbed;
+    }
+
+    /// Whether the message is of the given kind, skipping the parse entirely when the message
+    /// lacks text that kind requires
+    pub fn message_type_is(&self, kind: MessageKind) -> bool {
+        if let Some(text) = kind.required_text() {
+            if !self.message.contains(text) {
+                return false;
+            }
+        }
+        self.parse_message_type().kind() == kind
+    }
+
     pub fn parse_message_type(&self) -> MessageType {
-        MessageType::from_message(self.message.as_str())
+        MessageType::from_log_message(self)
     }
+
+    /// The timestamp's components, in the server's local time like the log itself
+    pub fn server_local_datetime(&self) -> LocalDateTime {
+        LocalDateTime {
+            year: self.timestamp.year(),
+            month: self.timestamp.month(),
+            day: self.timestamp.day(),
+            hour: self.timestamp.hour(),
+            minute: self.timestamp.minute(),
+            second: self.timestamp.second(),
+        }
+    }
+}
+
+/// The date and time of a log line as plain numbers, for consumers that don't want `chrono`
+/// in their own public API. See [`LogMessage::server_local_datetime`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalDateTime {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+    /// 1 to 31
+    pub day: u32,
+    /// 0 to 23
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+/// Parses each line of a pasted log snippet into its [`LogMessage`] and [`MessageType`].
+///
+/// Lines that aren't valid log lines, including blank ones, are skipped.
+pub fn parse_many(text: &str) -> Vec<(LogMessage, MessageType)> {
+    text.lines()
+        .filter_map(|line| LogMessage::from_str(line).ok())
+        .map(|msg| {
+            let message_type = msg.parse_message_type();
+            (msg, message_type)
+        })
+        .collect()
+}
+
+/// A log line whose timestamp is only parsed on demand, from
+/// [`LogMessage::from_bytes_lazy_timestamp`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LazyLogMessage {
+    /// The timestamp as logged, including its trailing `: `
+    pub raw_timestamp: String,
+    /// The raw string message with timestamps and headers removed.
+    pub message: String,
+    /// The received sv_logsecret or HTTP token, if the server sent one
+    pub secret: SecretKind,
+}
+
+impl LazyLogMessage {
+    /// Parses the raw timestamp, in either the standard or 12-hour format
+    pub fn timestamp(&self) -> Result<NaiveDateTime, LogParseError> {
+        parse_timestamp(&self.raw_timestamp).map(|(timestamp, _)| timestamp)
+    }
+}
+
+/// Splits the `[0xFFFFFFFF][R|S<secret>]L ` header off of a log line
+#[allow(clippy::len_zero, clippy::useless_conversion)]
+fn split_header(data: &[u8]) -> Result<(SecretKind, &[u8]), LogParseError> {
+    // parse off the header, which ends at the `L ` before the timestamp.
+    // long secrets may themselves contain `L ` so prefer one followed by a date,
+    // falling back to the first `L ` so a bad timestamp is reported as such.
+    // only look for the date before the first quote, so a date quoted in chat can't win
+    let header_end = data.iter().position(|&b| b == b'"').unwrap_or(data.len());
+    let separators =
+        || (0..data.len()).filter(|&idx| data[idx..].starts_with(&[MAGIC_STRING_END, b' ']));
+    let (header, rest) = match separators()
+        .take_while(|&idx| idx < header_end)
+        .find(|&idx| is_date_start(&data[(idx + 2)..]))
+        .or_else(|| separators().next())
+    {
+        Some(idx) => (&data[..idx], &data[(idx + 2)..]),
+        None if data.contains(&MAGIC_STRING_END) => return Err(LogParseError::TooShort),
+        None => return Err(LogParseError::NoMagicStringEnd),
+    };
+
+    let secret = if header.len() > 0 {
+        let mut header = header;
+        // udp packets start with four 0xFF bytes
+        if header.len() > 4 {
+            let udp_base = &header[..4];
+            if udp_base == PACKET_HEADER {
+                // cut them off
+                header = &header[4..];
+            }
+        }
+
+        // secret indication byte
+        let secret_byte = header[0];
+        if secret_byte == MAGIC_PASSWORD_BYTE {
+            // has secret, then grab
+            SecretKind::Udp(String::from_utf8_lossy(&header[1..]).to_string())
+        } else if secret_byte == MAGIC_NOPASSWORD_BYTE {
+            // no secret
+            SecretKind::None
+        } else {
+            // there is a header, but it's not a password byte, so error
+            return Err(LogParseError::BadPasswordByte(secret_byte).into());
+        }
+    } else {
+        // no header = no secret
+        SecretKind::None
+    };
+
+    Ok((secret, rest))
+}
+
+/// Parses a timestamp off the start of `data`, falling back to the 12-hour format
+fn parse_timestamp(data: &str) -> Result<(NaiveDateTime, &str), LogParseError> {
+    NaiveDateTime::parse_and_remainder(data, TIMESTAMP_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_and_remainder(data, TIMESTAMP_FORMAT_12H))
+        .map_err(|_| LogParseError::BadTimestamp)
+}
+
+/// Whether `data` starts with an `MM/DD/YYYY` date
+fn is_date_start(data: &[u8]) -> bool {
+    const SHAPE: &[u8] = b"00/00/0000";
+    data.len() >= SHAPE.len()
+        && SHAPE.iter().zip(data).all(|(&shape, &b)| match shape {
+            b'0' => b.is_ascii_digit(),
+            _ => b == shape,
+        })
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod tests {
     use super::*;
 
@@ -115,7 +414,7 @@ mod tests {
             parsed.message
                 == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
         );
-        assert!(parsed.secret.is_none());
+        assert!(parsed.secret == SecretKind::None);
     }
 
     #[test]
@@ -126,7 +425,7 @@ mod tests {
             parsed.message
                 == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
         );
-        assert!(parsed.secret.is_none());
+        assert!(parsed.secret == SecretKind::None);
     }
 
     #[test]
@@ -137,7 +436,7 @@ mod tests {
             parsed.message
                 == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
         );
-        assert!(parsed.secret.is_some_and(|s| s == "nya"));
+        assert!(parsed.secret == SecretKind::Udp("nya".to_owned()));
     }
 
     #[test]
@@ -150,7 +449,7 @@ mod tests {
             parsed.message
                 == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
         );
-        assert!(parsed.secret.is_some_and(|s| s == "nya"));
+        assert!(parsed.secret == SecretKind::Udp("nya".to_owned()));
     }
 
     #[test]
@@ -168,6 +467,326 @@ mod tests {
             parsed.message
                 == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
         );
-        assert!(parsed.secret.is_some_and(|s| s == "meow"));
+        assert!(parsed.secret == SecretKind::Udp("meow".to_owned()));
+    }
+
+    #[test]
+    fn twelve_hour_am() {
+        const LINE: &str = "L 02/09/2024 - 12:05:50 AM: \"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\"";
+        let parsed = LogMessage::from_str(LINE).unwrap();
+        assert!(parsed.timestamp.to_string() == "2024-02-09 00:05:50");
+        assert!(
+            parsed.message
+                == "\"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\""
+        );
+    }
+
+    #[test]
+    fn twelve_hour_pm() {
+        const LINE: &str = "L 02/09/2024 - 08:00:50 PM: \"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1\"";
+        let parsed = LogMessage::from_str(LINE).unwrap();
+        assert!(parsed.timestamp.to_string() == "2024-02-09 20:00:50");
+
+        const NOON: &str = "L 02/09/2024 - 12:00:00 PM: Log file closed";
+        let parsed = LogMessage::from_str(NOON).unwrap();
+        assert!(parsed.timestamp.to_string() == "2024-02-09 12:00:00");
+        assert!(parsed.message == "Log file closed");
+    }
+
+    #[test]
+    fn message_type_from_log_message() {
+        const LINE: &str = "L 02/09/2024 - 08:00:50: Log file closed";
+        let parsed = LogMessage::from_str(LINE).unwrap();
+        assert!(MessageType::from_log_message(&parsed) == MessageType::LogFileClosed);
+        assert!(MessageType::from(&parsed) == parsed.parse_message_type());
+    }
+
+    #[test]
+    fn truncated() {
+        let parsed = LogMessage::from_bytes(b"RL");
+        assert!(parsed.is_err_and(|e| e == LogParseError::TooShort));
+    }
+
+    #[test]
+    fn content_eq_ignores_secret() {
+        const FILE: &str = "L 02/09/2024 - 08:00:50: Log file closed";
+        const UDP: &str = "SnyaL 02/09/2024 - 08:00:50: Log file closed";
+        let file = LogMessage::from_str(FILE).unwrap();
+        let udp = LogMessage::from_str(UDP).unwrap();
+        assert!(file != udp);
+        assert!(file.content_eq(&udp));
+
+        const LATER: &str = "SnyaL 02/09/2024 - 08:00:51: Log file closed";
+        assert!(!file.content_eq(&LogMessage::from_str(LATER).unwrap()));
+    }
+
+    #[test]
+    fn anonymize_consistently() {
+        const CONNECT: &str = "L 02/09/2024 - 08:00:50: \"TheirUsername<6><[U:1:1324124512]><>\" connected, address \"192.168.0.1:27005\"";
+        const CHAT: &str =
+            "L 02/09/2024 - 08:01:50: \"TheirUsername<6><[U:1:1324124512]><Red>\" say \"hi\"";
+        let mut connect = LogMessage::from_str(CONNECT).unwrap();
+        let mut chat = LogMessage::from_str(CHAT).unwrap();
+        connect.anonymize("salt");
+        chat.anonymize("salt");
+        assert!(!connect.message.contains("TheirUsername"));
+        assert!(!chat.message.contains("1324124512"));
+
+        let (MessageType::Connected { user: a, .. }, MessageType::ChatMessage { from: b, .. }) =
+            (connect.parse_message_type(), chat.parse_message_type())
+        else {
+            panic!("anonymized messages no longer parse");
+        };
+        assert!(a.name == b.name);
+        assert!(a.steamid == b.steamid);
+
+        let mut other_salt = LogMessage::from_str(CHAT).unwrap();
+        other_salt.anonymize("pepper");
+        assert!(other_salt.message != chat.message);
+    }
+
+    #[test]
+    fn message_type_is() {
+        const CHAT: &str = "L 02/09/2024 - 08:00:50: \"User<1><[U:1:1]><Red>\" say \"killed \"";
+        let parsed = LogMessage::from_str(CHAT).unwrap();
+        assert!(parsed.message_type_is(MessageKind::ChatMessage));
+        assert!(!parsed.message_type_is(MessageKind::Killed));
+        assert!(!parsed.message_type_is(MessageKind::Unknown));
+    }
+
+    #[test]
+    fn secret_containing_l() {
+        const LINE: &str = "SheLLoL 02/09/2024 - 08:00:50: Log file closed";
+        let parsed = LogMessage::from_str(LINE).unwrap();
+        assert!(parsed.secret.as_str() == Some("heLLo"));
+        assert!(parsed.message == "Log file closed");
+    }
+
+    #[test]
+    fn secret_containing_l_space() {
+        const LINE: &str = "SaL bL cL 02/09/2024 - 08:00:50: Log file closed";
+        let parsed = LogMessage::from_str(LINE).unwrap();
+        assert!(parsed.secret.as_str() == Some("aL bL c"));
+        assert!(parsed.message == "Log file closed");
+
+        const BAD_TIMESTAMP: &str = "SaL nope";
+        assert!(LogMessage::from_str(BAD_TIMESTAMP) == Err(LogParseError::BadTimestamp));
+    }
+
+    #[test]
+    fn parse_many_skips_bad_lines() {
+        const SNIPPET: &str = "RL 02/09/2024 - 08:00:50: Log file closed\n\
+            not a log line\r\n\
+            RL 02/09/2024 - 08:00:51: Server cvars start\n\
+            RL 02/09/2024 - 08:00:52: Server cvars end";
+        let parsed = parse_many(SNIPPET);
+        let kinds: Vec<_> = parsed.iter().map(|(_, t)| t.clone()).collect();
+        assert!(
+            kinds
+                == vec![
+                    MessageType::LogFileClosed,
+                    MessageType::ServerCvarsStart,
+                    MessageType::ServerCvarsEnd
+                ]
+        );
+        assert!(parsed[2].0.message == "Server cvars end");
+    }
+
+    #[test]
+    fn secret_matches() {
+        let with_secret = LogMessage::from_str("Shunter2L 02/09/2024 - 08:00:50: Log file closed");
+        let with_secret = with_secret.unwrap();
+        assert!(with_secret.secret_matches("hunter2"));
+        assert!(!with_secret.secret_matches("hunter3"));
+        assert!(!with_secret.secret_matches("hunter"));
+        assert!(!with_secret.secret_matches(""));
+
+        let without = LogMessage::from_str("RL 02/09/2024 - 08:00:50: Log file closed").unwrap();
+        assert!(!without.secret_matches(""));
+        assert!(!without.secret_matches("hunter2"));
+    }
+
+    #[test]
+    fn prefix_skip() {
+        const LINE: &[u8] = b"[srv1] RL 02/09/2024 - 08:00:50: Log file closed";
+        let parsed = LogMessage::from_bytes_with_prefix_skip(LINE, |data| {
+            match data.iter().position(|&b| b == b']') {
+                Some(end) if data.starts_with(b"[") => data[(end + 1)..].trim_ascii_start(),
+                _ => data,
+            }
+        })
+        .unwrap();
+        assert!(parsed.message == "Log file closed");
+        assert!(LogMessage::from_bytes(LINE).is_err());
+    }
+
+    #[test]
+    fn secret_kinds() {
+        const FILE: &str = "L 02/09/2024 - 08:00:50: Log file closed";
+        assert!(LogMessage::from_str(FILE).unwrap().secret == SecretKind::None);
+
+        const UDP: &str = "SmeowL 02/09/2024 - 08:00:50: Log file closed";
+        let udp = LogMessage::from_str(UDP).unwrap();
+        assert!(udp.secret == SecretKind::Udp("meow".to_owned()));
+
+        const BODY: &[u8] =
+            b"02/09/2024 - 08:00:50: Log file closed\r\nL 02/09/2024 - 08:00:51: Log file started\n";
+        let http = LogMessage::from_http_body(BODY, Some("tok3n"));
+        assert!(http.len() == 2);
+        for msg in http {
+            let msg = msg.unwrap();
+            assert!(msg.secret == SecretKind::HttpToken("tok3n".to_owned()));
+            assert!(msg.secret_matches("tok3n"));
+        }
+        assert!(udp.content_eq(LogMessage::from_http_body(BODY, None)[0].as_ref().unwrap()));
+    }
+
+    #[test]
+    fn lazy_timestamp() {
+        const CORRUPT: &str = "L 02/3x/2024 - 08:00:50: Log file closed";
+        assert!(LogMessage::from_str(CORRUPT) == Err(LogParseError::BadTimestamp));
+        let lazy = LogMessage::from_bytes_lazy_timestamp(CORRUPT.as_bytes()).unwrap();
+        assert!(lazy.message == "Log file closed");
+        assert!(lazy.timestamp() == Err(LogParseError::BadTimestamp));
+
+        const GOOD: &str = "SmeowL 02/09/2024 - 08:00:50 PM: Log file closed";
+        let lazy = LogMessage::from_bytes_lazy_timestamp(GOOD.as_bytes()).unwrap();
+        let eager = LogMessage::from_str(GOOD).unwrap();
+        assert!(lazy.timestamp() == Ok(eager.timestamp));
+        assert!(lazy.message == eager.message && lazy.secret == eager.secret);
+    }
+
+    #[test]
+    fn mixed_timestamp_formats() {
+        const FORMATS: [&str; 2] = ["%m/%d/%Y - %H:%M:%S: ", "%Y-%m-%d %H:%M:%S: "];
+        let lines = [
+            "L 02/09/2024 - 08:00:50: Log file started",
+            "L 2024-02-09 08:00:51: Log file closed",
+        ];
+        let parsed: Vec<_> = lines
+            .iter()
+            .map(|line| LogMessage::from_bytes_try_formats(line.as_bytes(), &FORMATS).unwrap())
+            .collect();
+        assert!(parsed[0].message == "Log file started");
+        assert!(parsed[1].message == "Log file closed");
+        assert!(parsed[1].timestamp - parsed[0].timestamp == chrono::Duration::seconds(1));
+
+        let only_iso = LogMessage::from_bytes_try_formats(lines[0].as_bytes(), &FORMATS[1..]);
+        assert!(only_iso == Err(LogParseError::BadTimestamp));
+    }
+
+    #[test]
+    fn length_limit() {
+        const LINE: &[u8] = b"L 02/09/2024 - 08:00:50: Log file closed";
+        assert!(LogMessage::from_bytes_limited(LINE, LINE.len()).is_ok());
+        assert!(
+            LogMessage::from_bytes_limited(LINE, LINE.len() - 1) == Err(LogParseError::TooLong)
+        );
+    }
+
+    #[test]
+    fn max_line_nonzero() {
+        const _: () = assert!(MAX_LOG_LINE > 0);
+        let line = format!("L 02/09/2024 - 08:00:50: {}", "a".repeat(MAX_LOG_LINE));
+        assert!(
+            LogMessage::from_bytes_limited(line.as_bytes(), MAX_LOG_LINE)
+                == Err(LogParseError::TooLong)
+        );
+
+        let mut packet = PACKET_HEADER.to_vec();
+        packet.push(MAGIC_PASSWORD_BYTE);
+        packet.extend("s".repeat(MAX_SECRET).bytes());
+        packet.extend(b"L ");
+        packet.extend(b"02/09/2024 - 08:00:50: ");
+        packet.resize(MAX_PACKET, b'a');
+        let parsed = LogMessage::from_bytes_limited(&packet, MAX_PACKET).unwrap();
+        assert!(parsed.secret_matches(&"s".repeat(MAX_SECRET)));
+    }
+
+    #[test]
+    fn local_datetime_components() {
+        let parsed = LogMessage::from_str("L 02/09/2024 - 20:05:50: Log file closed").unwrap();
+        assert!(
+            parsed.server_local_datetime()
+                == LocalDateTime {
+                    year: 2024,
+                    month: 2,
+                    day: 9,
+                    hour: 20,
+                    minute: 5,
+                    second: 50,
+                }
+        );
+    }
+
+    #[test]
+    fn anonymize_every_user_form() {
+        const ACCOLADE: &str = "L 02/09/2024 - 08:00:50: ACCOLADE, FINAL: {3k},\tSecretName<12>,\tVALUE: 2.000000,\tPOS: 1,\tSCORE: 40.000000";
+        const UNQUOTED: &str = "L 02/09/2024 - 08:00:50: SecretName<1><[U:1:555]><Red> say \"hi\"";
+        for line in [ACCOLADE, UNQUOTED] {
+            let mut msg = LogMessage::from_str(line).unwrap();
+            msg.anonymize("salt");
+            assert!(!msg.message.contains("SecretName"), "{}", msg.message);
+            assert!(!msg.message.contains("555"), "{}", msg.message);
+            assert!(
+                msg.parse_message_type().kind()
+                    == LogMessage::from_str(line)
+                        .unwrap()
+                        .parse_message_type()
+                        .kind()
+            );
+        }
+
+        // pseudonyms follow the steamid, not the name, and never change between Rust versions
+        let pseudonym = |name: &str| {
+            let mut user = User {
+                name: name.to_owned(),
+                uid: 1,
+                steamid: "[U:1:1]".to_owned(),
+                team: "Red".to_owned(),
+            };
+            user.anonymize("salt");
+            user
+        };
+        assert!(pseudonym("A") == pseudonym("renamed"));
+        assert!(pseudonym("A").name == "Player-52dc442836cd9e55");
+    }
+
+    #[test]
+    fn scrub_raw_ips() {
+        const LINE: &str =
+            "L 02/09/2024 - 08:00:50: \"A<1><[U:1:1]><>\" connected, address \"192.168.0.1:27005\"";
+        let mut msg = LogMessage::from_str(LINE).unwrap();
+        msg.scrub_ips();
+        assert!(msg.message == "\"A<1><[U:1:1]><>\" connected, address \"0.0.0.0:27005\"");
+
+        const VERSION: &str = "L 02/09/2024 - 08:00:50: Log file started (file \"a.log\") (game \"tf\") (version \"1.2.3.4.5\")";
+        let mut msg = LogMessage::from_str(VERSION).unwrap();
+        msg.scrub_ips();
+        assert!(msg.message.contains("1.2.3.4.5"));
+    }
+
+    #[test]
+    fn quoted_date_in_other_format() {
+        const LINE: &str = "L 2024-02-09 08:00:51: \"A<1><[U:1:1]><Red>\" say \"L 01/01/2020 lol\"";
+        let parsed =
+            LogMessage::from_bytes_try_formats(LINE.as_bytes(), &["%Y-%m-%d %H:%M:%S: "]).unwrap();
+        assert!(parsed.message == "\"A<1><[U:1:1]><Red>\" say \"L 01/01/2020 lol\"");
+        assert!(parsed.secret == SecretKind::None);
+    }
+
+    #[test]
+    fn anonymized_bot_stays_bot() {
+        let mut bot = User {
+            name: "Bot Scout".to_owned(),
+            uid: 3,
+            steamid: "BOT".to_owned(),
+            team: "Red".to_owned(),
+        };
+        let key = bot.identity_key();
+        bot.anonymize("salt");
+        assert!(bot.is_bot());
+        assert!(bot.identity_key() == key);
+        assert!(bot.name != "Bot Scout");
     }
 }
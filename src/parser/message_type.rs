@@ -1,10 +1,15 @@
-use std::net::Ipv4Addr;
+use super::LogMessage;
+use std::{
+    hash::{Hash, Hasher},
+    net::{IpAddr, Ipv4Addr},
+};
 
 mod parsers;
 use parsers::*;
 
 /// https://developer.valvesoftware.com/wiki/HL_Log_Standard#Appendix_B_-_Example_Log_Files
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageType {
     LogFileStarted {
         file: String,
@@ -13,21 +18,28 @@ pub enum MessageType {
     },
     LogFileClosed,
     ServerCvarsStart,
+    /// `server_cvar: "<var>" "<value>"` or `"<var>" = "<value>"`, the latter optionally
+    /// followed by a `( def. "<default>" min. "<min>" max. "<max>" )` trailer
     ServerCvar {
         var: String,
         value: String,
+        default: Option<String>,
+        min: Option<String>,
+        max: Option<String>,
     },
     ServerCvarsEnd,
+    /// `Loading map "<name>"`, followed by `(spawnflags "<n>")` style properties on some builds
     LoadingMap {
         name: String,
+        properties: Properties,
     },
+    /// `Started map "<name>"`, followed by `(CRC "<crc>")` on all but older engine builds
     StartedMap {
         name: String,
-        crc: String,
+        crc: Option<String>,
     },
     Rcon {
-        ip: Ipv4Addr,
-        port: u16,
+        source: RconSource,
         command: String,
     },
     ChatMessage {
@@ -35,10 +47,13 @@ pub enum MessageType {
         message: String,
         team: bool,
     },
+    /// `"<user>" connected, address "<ip>:<port>"` followed by any `(key "value")` properties.
+    /// Bots, including SourceTV, connect with address `none`, which is recorded as `0.0.0.0:0`.
     Connected {
         user: User,
         ip: Ipv4Addr,
         port: u16,
+        properties: Properties,
     },
     Disconnected {
         user: User,
@@ -52,12 +67,387 @@ pub enum MessageType {
         from: User,
         action: String,
         against: User,
+        properties: Properties,
+    },
+    /// `Public IP is <ip>.`
+    PublicIp {
+        ip: IpAddr,
+    },
+    /// `Network: IP <ip>, mode MP, dedicated Yes, ports <sv_port> SV / <cl_port> CL`
+    NetworkConfig {
+        ip: IpAddr,
+        sv_port: u16,
+        cl_port: u16,
+    },
+    /// `Executing dedicated server config file <file>` or `exec: couldn't exec <file>`
+    ExecConfig {
+        file: String,
+        success: bool,
+    },
+    /// `Assigning unique id <index> to "<name>"`, logged while a player connects
+    PlayerIndexAssigned {
+        name: String,
+        index: u32,
+    },
+    /// `Server message: "<text>"`, e.g. from `sm_say`
+    ServerMessage {
+        text: String,
+    },
+    /// An `addip` / `removeip` / `banid` / `removeid` command echo, `target` being its last
+    /// argument
+    BanListChange {
+        action: String,
+        target: String,
+    },
+    /// Steam / game coordinator connection status
+    BackendStatus {
+        event: BackendEvent,
+    },
+    /// `"<user>" killed "<user>" with "<weapon>"`, with an optional inline `(assist "<user>")`
+    ///
+    /// CS-style inline `[x y z]` positions after either user are skipped.
+    Killed {
+        attacker: User,
+        victim: User,
+        weapon: String,
+        assister: Option<User>,
+    },
+    /// `"<user>" committed suicide with "<weapon>"`
+    Suicide {
+        user: User,
+        weapon: String,
+    },
+    /// `"<user>" triggered "domination" against "<user>"`
+    Domination {
+        from: User,
+        against: User,
+        from_position: Option<Position>,
+        against_position: Option<Position>,
+        /// The `(weapon "<weapon>")` some servers append
+        weapon: Option<String>,
+    },
+    /// `"<user>" triggered "revenge" against "<user>"`
+    Revenge {
+        from: User,
+        against: User,
+        from_position: Option<Position>,
+        against_position: Option<Position>,
+        /// The `(weapon "<weapon>")` some servers append
+        weapon: Option<String>,
+    },
+    /// `World triggered "Mann Vs Machine Event" (event "<event>") (wave "<wave>")`
+    MvmWave {
+        event: MvmWaveEvent,
+        wave: u32,
+    },
+    /// `Team "<team>" current score "<score>" with "<players>" players`, or CS's
+    /// `Team "<team>" scored "<score>" with "<players>" players`
+    TeamCurrentScore {
+        team: String,
+        score: u32,
+        players: u32,
+    },
+    /// `Team "<team>" final score "<score>" with "<players>" players`
+    TeamFinalScore {
+        team: String,
+        score: u32,
+        players: u32,
+    },
+    /// `"<user>" triggered "<action>"` followed by any `(key "value")` properties
+    PlayerTriggered {
+        user: User,
+        action: String,
+        properties: Properties,
+    },
+    /// `Team "<team>" triggered "<action>"` followed by any `(key "value")` properties
+    TeamTriggered {
+        team: String,
+        action: String,
+        properties: Properties,
+    },
+    /// `World triggered "<action>"` followed by any `(key "value")` properties
+    WorldTriggered {
+        action: String,
+        properties: Properties,
+    },
+    /// CS:GO `MatchStatus: Score: <ct>:<t> on map "<map>" RoundsPlayed: <rounds>`
+    MatchStatus {
+        ct_score: u32,
+        t_score: u32,
+        map: String,
+        rounds: u32,
+    },
+    /// CS:GO `ACCOLADE, FINAL: {<category>},\t<name><<uid>>,\tVALUE: ...` end of match awards
+    Accolade(Accolade),
+    /// `"<user>" triggered "object_detonated" (object "<object>")`, an engineer destroying their
+    /// own building
+    ObjectDetonated {
+        user: User,
+        object: BuildingType,
     },
+    /// CS `"<user>" [x y z] attacked "<user>" [x y z] with "<weapon>" (damage "<n>") ...` damage
+    CsAttack {
+        attacker: User,
+        attacker_position: Option<Position>,
+        victim: User,
+        victim_position: Option<Position>,
+        weapon: String,
+        damage: u32,
+        damage_armor: u32,
+        health: u32,
+        armor: u32,
+        hitgroup: String,
+    },
+    /// CS `"<user>" money change <before>-<amount> = $<after>`, with optional `(tracked)` and
+    /// `(purchase: <item>)` tails
+    MoneyChange {
+        user: User,
+        before: i32,
+        delta: i32,
+        after: i32,
+        purchase: Option<String>,
+    },
+    /// CS `"<user>" triggered "Planted_The_Bomb"` and the other bomb triggers
+    BombEvent {
+        user: User,
+        event: BombAction,
+    },
+    /// `"<user>" triggered "flagevent" (event "<event>")`, with an optional `(position "x y z")`
+    FlagEvent {
+        user: User,
+        event: FlagEventKind,
+        position: Option<Position>,
+    },
+    /// `Host_Error: <text>`, `FATAL ERROR <text>`, `Segmentation fault` or a failed `Assert`,
+    /// the server crashing. Only the text after the first two markers is kept.
+    FatalError {
+        text: String,
+    },
+    /// `Error: <text>` or `Warning: <text>`, a problem the server recovers from
+    EngineError {
+        text: String,
+    },
+    /// `"<user>" triggered "killedobject" (object "<object>") (weapon "<weapon>")` with an
+    /// `(objectowner "<user>")`, `owner` being `None` when the building had no player owner,
+    /// e.g. `World`
+    KilledObject {
+        attacker: User,
+        object: BuildingType,
+        weapon: Option<String>,
+        owner: Option<User>,
+        /// From an `(assist "<user>")` property
+        assister: Option<User>,
+    },
+    /// Insurgency `"<user>" triggered "captured_point" (point "<point>")`
+    CapturedPoint {
+        user: User,
+        point: String,
+    },
+    /// `Map: <name>` status lines and `changelevel <name>` echoes
+    MapStatus {
+        name: String,
+    },
+    /// `"<user>" triggered "captureblocked" (cp "<cp>") (cpname "<name>")`, with the blocker's
+    /// position from either a `(position "x y z")` property or an inline `[x y z]` after the user
+    CaptureBlocked {
+        user: User,
+        /// Usually a number, but not on every payload or arena map
+        cp: String,
+        cpname: String,
+        position: Option<Position>,
+        /// Every logged property, including map specific ones
+        properties: Properties,
+    },
+    /// `"<user>" triggered "damage" against "<user>" (damage "<amount>")`, with optional
+    /// `(weapon "<weapon>")` and `(crit "<crit>")` properties
+    Damage {
+        attacker: User,
+        victim: User,
+        amount: u32,
+        weapon: Option<String>,
+        crit: Option<String>,
+    },
+    /// `-> Reservation cookie <cookie>:  reason [R] ...`, logged by matchmaking servers when a
+    /// lobby reserves them
+    Reservation {
+        cookie: String,
+    },
+    /// `"<user>" position "<x y z>" angles "<pitch yaw roll>"`, a periodic heartbeat some
+    /// plugins log for movement replays
+    PlayerPosition {
+        user: User,
+        position: Position,
+        angles: Option<Angles>,
+    },
+    /// Engine performance diagnostics like `Server frame ...` or `fps drop ...`, kept whole
+    Diagnostic {
+        text: String,
+    },
+    /// `"<user>" triggered "player_spawn" (class "<class>") (team "<team>")`, with `team` taken
+    /// from the user when the property is missing
+    PlayerSpawn {
+        user: User,
+        class: TfClass,
+        team: Team,
+    },
+    /// Left 4 Dead 2 `"<victim>" triggered "incapacitated" against "<attacker>"`, a survivor
+    /// going down
+    Incapacitated {
+        victim: User,
+        attacker: User,
+    },
+    /// Left 4 Dead 2 `"<reviver>" triggered "revived" against "<revived>"`, a downed survivor
+    /// picked back up
+    Revived {
+        reviver: User,
+        revived: User,
+    },
+    /// `Server shutting down`, or the `quit` command echoed as the server exits gracefully
+    ServerShutdown,
+    Unknown,
+}
+
+/// The variant of a [`MessageType`], without its data
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageKind {
+    LogFileStarted,
+    LogFileClosed,
+    ServerCvarsStart,
+    ServerCvar,
+    ServerCvarsEnd,
+    LoadingMap,
+    StartedMap,
+    Rcon,
+    ChatMessage,
+    Connected,
+    Disconnected,
+    JoinedTeam,
+    InterPlayerAction,
+    PublicIp,
+    NetworkConfig,
+    ExecConfig,
+    PlayerIndexAssigned,
+    BackendStatus,
+    ServerMessage,
+    BanListChange,
+    Killed,
+    Suicide,
+    Domination,
+    Revenge,
+    MvmWave,
+    TeamCurrentScore,
+    TeamFinalScore,
+    PlayerTriggered,
+    TeamTriggered,
+    WorldTriggered,
+    MatchStatus,
+    Accolade,
+    ObjectDetonated,
+    CsAttack,
+    MoneyChange,
+    BombEvent,
+    FlagEvent,
+    FatalError,
+    EngineError,
+    KilledObject,
+    CapturedPoint,
+    MapStatus,
+    CaptureBlocked,
+    Damage,
+    Reservation,
+    PlayerPosition,
+    Diagnostic,
+    PlayerSpawn,
+    Incapacitated,
+    Revived,
+    ServerShutdown,
     Unknown,
 }
 
+impl MessageKind {
+    /// Text a message must contain to possibly be of this kind, used to skip parsing
+    pub(crate) fn required_text(&self) -> Option<&'static str> {
+        match self {
+            Self::ChatMessage => Some(" say"),
+            Self::Connected => Some(" connected, address "),
+            Self::Disconnected => Some(" disconnected "),
+            Self::JoinedTeam => Some(" joined team "),
+            Self::Killed => Some(" killed "),
+            Self::CsAttack => Some(" attacked "),
+            Self::MoneyChange => Some(" money change "),
+            Self::Suicide => Some(" committed suicide "),
+            Self::PlayerPosition => Some(" position "),
+            Self::InterPlayerAction
+            | Self::Domination
+            | Self::Revenge
+            | Self::Damage
+            | Self::Incapacitated
+            | Self::Revived => Some(" against "),
+            Self::PlayerTriggered
+            | Self::TeamTriggered
+            | Self::WorldTriggered
+            | Self::ObjectDetonated
+            | Self::BombEvent
+            | Self::FlagEvent
+            | Self::KilledObject
+            | Self::CapturedPoint
+            | Self::CaptureBlocked
+            | Self::PlayerSpawn => Some(" triggered "),
+            _ => None,
+        }
+    }
+}
+
+/// Selects which groups of parsers run, so specialized consumers can skip the ones they
+/// don't need. Anything matched only by a disabled group parses as [`MessageType::Unknown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserConfig {
+    /// Log files, cvars, maps, rcon and other server status lines
+    pub server: bool,
+    /// `say` / `say_team`
+    pub chat: bool,
+    /// Connects, disconnects and team changes
+    pub connections: bool,
+    /// Kills, damage, suicides, dominations and revenges
+    pub kills: bool,
+    /// Player, world and player-against-player triggers
+    pub triggers: bool,
+    /// Team scores, match status, accolades and money changes
+    pub scores: bool,
+}
+
+impl ParserConfig {
+    /// Every parser disabled
+    pub fn none() -> Self {
+        Self {
+            server: false,
+            chat: false,
+            connections: false,
+            kills: false,
+            triggers: false,
+            scores: false,
+        }
+    }
+}
+
+impl Default for ParserConfig {
+    /// Every parser enabled
+    fn default() -> Self {
+        Self {
+            server: true,
+            chat: true,
+            connections: true,
+            kills: true,
+            triggers: true,
+            scores: true,
+        }
+    }
+}
+
 /// A source user's data
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     pub name: String,
     pub uid: u32,
@@ -65,7 +455,524 @@ pub struct User {
     pub team: String,
 }
 
+impl User {
+    /// Whether this is a bot, which have `BOT` in place of a steamid
+    pub fn is_bot(&self) -> bool {
+        self.steamid == "BOT"
+    }
+
+    /// A key that tells players apart: the steamid, or `BOT:<uid>` for bots, which all share
+    /// the `BOT` steamid
+    pub fn identity_key(&self) -> String {
+        if self.is_bot() {
+            format!("BOT:{}", self.uid)
+        } else {
+            self.steamid.clone()
+        }
+    }
+
+    /// Whether this is the SourceTV / GOTV relay bot rather than a player.
+    ///
+    /// The relay is recognized by its default name, `SourceTV`, `GOTV` or `HLTV`, so a
+    /// relay renamed with `tv_name` counts only as a bot.
+    pub fn is_tv(&self) -> bool {
+        self.is_bot() && ["SourceTV", "GOTV", "HLTV"].contains(&self.name.as_str())
+    }
+
+    /// Replaces the name and steamid with pseudonyms derived from the salted
+    /// [`User::identity_key`], so the same player maps to the same pseudonym everywhere the same
+    /// salt is used, across runs and Rust versions.
+    ///
+    /// Bots keep their `BOT` steamid, and users logged without a steamid, like those of
+    /// accolades, are told apart by name instead and keep their empty steamid.
+    ///
+    /// Pseudonymous steamids keep the `[U:1:<account>]` form so anonymized lines still parse,
+    /// which means one can collide with a real player's; don't mix anonymized and real logs.
+    /// This is not a cryptographic hash; keep the salt private.
+    pub fn anonymize(&mut self, salt: &str) {
+        let identity = if self.steamid.is_empty() {
+            self.name.clone()
+        } else {
+            self.identity_key()
+        };
+        let hash = |field: &str| stable_hash(&[salt, field, &identity]);
+        let (name, account) = (hash("name"), hash("steamid") as u32);
+        self.name = format!("Player-{name:016x}");
+        if !self.steamid.is_empty() && !self.is_bot() {
+            self.steamid = format!("[U:1:{account}]");
+        }
+    }
+
+    /// Heuristically splits a clan tag prefix off of the name, returning `(tag, nickname)`.
+    ///
+    /// A tag is either a bracketed prefix, `[CLAN] Nick`, `(CLAN)Nick` or `{CLAN} Nick`,
+    /// or whatever comes before the first `|`, `::`, `//` or `~` separator, `CLAN | Nick`.
+    /// The tag is returned without its brackets, and both parts are trimmed. Names that
+    /// would be left with an empty tag or nickname are treated as having no tag.
+    pub fn split_clantag(&self) -> (Option<String>, String) {
+        const BRACKETS: [(char, char); 3] = [('[', ']'), ('(', ')'), ('{', '}')];
+        const SEPARATORS: [&str; 4] = ["|", "::", "//", "~"];
+
+        let name = self.name.trim();
+        let split = BRACKETS
+            .iter()
+            .find_map(|&(open, close)| {
+                let (tag, nick) = name.strip_prefix(open)?.split_once(close)?;
+                Some((tag, nick))
+            })
+            .or_else(|| {
+                SEPARATORS
+                    .iter()
+                    .filter_map(|sep| name.split_once(sep))
+                    .min_by_key(|(tag, _)| tag.len())
+            });
+        match split.map(|(tag, nick)| (tag.trim(), nick.trim())) {
+            Some((tag, nick)) if !tag.is_empty() && !nick.is_empty() => {
+                (Some(tag.to_owned()), nick.to_owned())
+            }
+            _ => (None, name.to_owned()),
+        }
+    }
+}
+
+/// The `event` of a Mann vs. Machine world trigger
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MvmWaveEvent {
+    WaveStart,
+    WaveComplete,
+    WaveFailed,
+    MissionComplete,
+}
+
+/// A recognized Steam or game coordinator connection status line
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackendEvent {
+    /// `Connection to Steam servers successful.`
+    SteamConnected,
+    /// `Connection to Steam servers lost.` / `Lost connection to Steam servers`
+    SteamDisconnected,
+    /// `Connection to game coordinator established.`
+    GcConnected,
+    /// `Connection to game coordinator lost.` / `Lost connection to game coordinator`
+    GcDisconnected,
+}
+
+/// Where an `rcon from` command was sent from
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RconSource {
+    /// `"1.2.3.4:27015"`
+    Ip(IpAddr, u16),
+    /// `"admin.example.com:27015"`, when the source is logged by hostname
+    Host(String, u16),
+}
+
+impl RconSource {
+    /// The port the command was sent from
+    pub fn port(&self) -> u16 {
+        match self {
+            Self::Ip(_, port) | Self::Host(_, port) => *port,
+        }
+    }
+}
+
+/// A CS:GO end of match award
+///
+/// Accolade lines only log the player's name and uid, so `steamid` and `team` are empty.
+/// `value` and `score` compare bitwise, keeping [`MessageType`] `Eq` and `Hash`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Accolade {
+    /// e.g. `3k`, `burndamage`
+    pub category: String,
+    pub user: User,
+    pub value: f32,
+    /// 1-based rank among the players given this accolade
+    pub position: u32,
+    pub score: f32,
+}
+
+impl Accolade {
+    fn key(&self) -> (&str, &User, u32, u32, u32) {
+        let Self {
+            category,
+            user,
+            value,
+            position,
+            score,
+        } = self;
+        (category, user, value.to_bits(), *position, score.to_bits())
+    }
+}
+
+impl PartialEq for Accolade {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Accolade {}
+
+impl Hash for Accolade {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+/// An engineer picking up or putting down one of their buildings
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ObjectMovement {
+    /// `player_carryobject`
+    CarryObject,
+    /// `player_dropobject`
+    DropObject,
+}
+
+/// The `event` of a capture the flag `flagevent` trigger
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlagEventKind {
+    /// `picked up`
+    PickedUp,
+    /// `dropped`
+    Dropped,
+    /// `captured`
+    Captured,
+    /// `defended`, killing the carrier of your own flag
+    Defended,
+}
+
+/// The `action` of a CS bomb trigger
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BombAction {
+    /// `Got_The_Bomb`
+    Picked,
+    /// `Dropped_The_Bomb`
+    Dropped,
+    /// `Planted_The_Bomb`
+    Planted,
+    /// `Began_Bomb_Defuse_With_Kit` / `Began_Bomb_Defuse_Without_Kit`
+    BeganDefuse { with_kit: bool },
+    /// `Defused_The_Bomb`
+    Defused,
+}
+
+/// A TF2 player class, from the `class` property of spawn triggers
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TfClass {
+    Scout,
+    Soldier,
+    Pyro,
+    Demoman,
+    /// `heavyweapons`
+    Heavy,
+    Engineer,
+    Medic,
+    Sniper,
+    Spy,
+    Other(String),
+}
+
+impl From<&str> for TfClass {
+    fn from(class: &str) -> Self {
+        match class.to_ascii_lowercase().as_str() {
+            "scout" => Self::Scout,
+            "soldier" => Self::Soldier,
+            "pyro" => Self::Pyro,
+            "demoman" => Self::Demoman,
+            "heavyweapons" | "heavy" => Self::Heavy,
+            "engineer" => Self::Engineer,
+            "medic" => Self::Medic,
+            "sniper" => Self::Sniper,
+            "spy" => Self::Spy,
+            _ => Self::Other(class.to_owned()),
+        }
+    }
+}
+
+/// A TF2 team, from the `team` property of spawn triggers
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Team {
+    Red,
+    Blue,
+    Spectator,
+    Unassigned,
+    Other(String),
+}
+
+impl From<&str> for Team {
+    fn from(team: &str) -> Self {
+        match team.to_ascii_lowercase().as_str() {
+            "red" => Self::Red,
+            "blue" => Self::Blue,
+            "spectator" => Self::Spectator,
+            "unassigned" => Self::Unassigned,
+            _ => Self::Other(team.to_owned()),
+        }
+    }
+}
+
+/// The `crit` property of a TF2 damage trigger
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CritKind {
+    /// `crit`
+    Crit,
+    /// `minicrit`
+    MiniCrit,
+}
+
+/// An engineer building, from the `object` property of building triggers
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BuildingType {
+    /// `OBJ_SENTRYGUN`, including mini-sentries
+    Sentry,
+    /// `OBJ_DISPENSER`
+    Dispenser,
+    /// `OBJ_TELEPORTER`, when the log doesn't say which end
+    Teleporter,
+    /// `OBJ_TELEPORTER_ENTRANCE`
+    TeleporterEntrance,
+    /// `OBJ_TELEPORTER_EXIT`
+    TeleporterExit,
+    /// `OBJ_ATTACHMENT_SAPPER`
+    Sapper,
+    Other(String),
+}
+
+impl From<&str> for BuildingType {
+    fn from(object: &str) -> Self {
+        match object {
+            "OBJ_SENTRYGUN" => Self::Sentry,
+            "OBJ_DISPENSER" => Self::Dispenser,
+            "OBJ_TELEPORTER" => Self::Teleporter,
+            "OBJ_TELEPORTER_ENTRANCE" => Self::TeleporterEntrance,
+            "OBJ_TELEPORTER_EXIT" => Self::TeleporterExit,
+            "OBJ_ATTACHMENT_SAPPER" => Self::Sapper,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// The game a log belongs to, from the `game` of `Log file started`.
+///
+/// Servers often log the full path to the game directory, so only its last component is used.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Game {
+    /// `tf`
+    Tf2,
+    /// `cstrike`, shared by Counter-Strike 1.6 and most Counter-Strike: Source servers
+    CounterStrike,
+    /// `csgo`
+    Csgo,
+    /// `css`
+    Css,
+    /// `left4dead2`
+    L4d2,
+    /// Any other game directory
+    Other(String),
+}
+
+impl From<&str> for Game {
+    fn from(game: &str) -> Self {
+        let game = game.rsplit(['/', '\\']).next().unwrap_or(game);
+        match game {
+            "tf" => Self::Tf2,
+            "cstrike" => Self::CounterStrike,
+            "csgo" => Self::Csgo,
+            "css" => Self::Css,
+            "left4dead2" => Self::L4d2,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// 64-bit FNV-1a over `parts`, each ending in a 0 byte so where they split matters.
+///
+/// Unlike `DefaultHasher`, the output is fixed, so pseudonyms stay reproducible.
+fn stable_hash(parts: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    parts
+        .iter()
+        .flat_map(|part| part.bytes().chain([0]))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+/// Whether `msg` could have come from a log of `game`, to catch archives labelled with the
+/// wrong game.
+///
+/// This is a heuristic: it only flags team names and events that belong to another game, like a
+/// `TERRORIST` on a `tf` log or a building kill on a `cstrike` one. Any message is plausible for
+/// [`Game::Other`], and events of games without a [`Game`] of their own, like Insurgency's
+/// [`MessageKind::CapturedPoint`], are plausible for every game.
+pub fn is_plausible_for(game: Game, msg: &MessageType) -> bool {
+    const TF2_TEAMS: [&str; 2] = ["Red", "Blue"];
+    const CS_TEAMS: [&str; 2] = ["TERRORIST", "CT"];
+    const L4D2_TEAMS: [&str; 2] = ["Survivor", "Infected"];
+
+    let tf2_only = matches!(
+        msg.kind(),
+        MessageKind::MvmWave
+            | MessageKind::ObjectDetonated
+            | MessageKind::KilledObject
+            | MessageKind::CaptureBlocked
+            | MessageKind::PlayerSpawn
+    );
+    let cs_only = matches!(
+        msg.kind(),
+        MessageKind::CsAttack
+            | MessageKind::BombEvent
+            | MessageKind::MoneyChange
+            | MessageKind::MatchStatus
+            | MessageKind::Accolade
+    );
+    let l4d2_only = matches!(
+        msg.kind(),
+        MessageKind::Incapacitated | MessageKind::Revived
+    );
+    let team = msg.affected_team().unwrap_or_default();
+    let foreign_teams: &[[&str; 2]] = match game {
+        Game::Tf2 if cs_only || l4d2_only => return false,
+        Game::CounterStrike | Game::Csgo | Game::Css if tf2_only || l4d2_only => return false,
+        Game::L4d2 if tf2_only || cs_only => return false,
+        Game::Tf2 => &[CS_TEAMS, L4D2_TEAMS],
+        Game::CounterStrike | Game::Csgo | Game::Css => &[TF2_TEAMS, L4D2_TEAMS],
+        Game::L4d2 => &[TF2_TEAMS, CS_TEAMS],
+        Game::Other(_) => &[],
+    };
+    !foreign_teams
+        .iter()
+        .flatten()
+        .any(|&foreign| foreign == team)
+}
+
+/// A position in the world, as logged in `(attacker_position "x y z")` style properties
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+/// The trailing `(key "value")` pairs of a message, in the order they were logged
+#[derive(Debug, PartialEq, Eq, Clone, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Properties(pub Vec<(String, String)>);
+
+impl Properties {
+    /// The value of the first property named `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The value of `key` as a `u32`, `None` if it's missing or not a number
+    pub fn get_u32(&self, key: &str) -> Option<u32> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// The value of `key` as an `f32`, `None` if it's missing or not a number
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// The value of `key` as an `x y z` position, like `(attacker_position "1 2 3")`
+    pub fn get_position(&self, key: &str) -> Option<Position> {
+        parsers::position(self.get(key)?).ok().map(|(_, p)| p)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (String, String)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<(String, String)>> for Properties {
+    fn from(properties: Vec<(String, String)>) -> Self {
+        Self(properties)
+    }
+}
+
+impl IntoIterator for Properties {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Properties {
+    type Item = &'a (String, String);
+    type IntoIter = std::slice::Iter<'a, (String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A view direction in degrees, as logged in `angles "pitch yaw roll"`.
+///
+/// Compares bitwise, keeping [`MessageType`] `Eq` and `Hash`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Angles {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+impl Angles {
+    fn key(&self) -> (u32, u32, u32) {
+        (
+            self.pitch.to_bits(),
+            self.yaw.to_bits(),
+            self.roll.to_bits(),
+        )
+    }
+}
+
+impl PartialEq for Angles {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Angles {}
+
+impl Hash for Angles {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
 impl MessageType {
+    /// Parses the message type of an already parsed log line
+    pub fn from_log_message(msg: &LogMessage) -> Self {
+        Self::from_message(msg.message.as_str())
+    }
+
     pub fn from_message<'a>(msg: impl Into<&'a str>) -> Self {
         match get_message_type(msg.into()) {
             Ok((_, m)) => m,
@@ -73,10 +980,492 @@ impl MessageType {
         }
     }
 
+    /// Like [`MessageType::from_message`], but when a user tag is malformed (e.g. a corrupt
+    /// steamid) it is replaced by a placeholder [`User`] holding the raw tag text in `name`,
+    /// with a `uid` of 0 and empty `steamid` / `team`, instead of the whole line being unknown.
+    ///
+    /// Lenient mode trades correctness for recall: a quoted value that merely looks like a
+    /// user tag may be misread as one, so prefer strict parsing where accuracy matters.
+    pub fn from_message_lenient<'a>(msg: impl Into<&'a str>) -> Self {
+        let msg = msg.into();
+        let strict = Self::from_message(msg);
+        if !strict.is_unknown() {
+            return strict;
+        }
+        let mut lenient = Self::from_message(patch_user_tags(msg).as_str());
+        for user in lenient.users_mut() {
+            if user.steamid == PLACEHOLDER_STEAMID {
+                user.steamid.clear();
+            }
+        }
+        lenient
+    }
+
+    /// Parses a message using only the parsers enabled in `config`
+    pub fn from_message_with_config<'a>(msg: impl Into<&'a str>, config: &ParserConfig) -> Self {
+        match get_message_type_with_config(msg.into(), config) {
+            Ok((_, m)) => m,
+            Err(_) => MessageType::Unknown,
+        }
+    }
+
+    /// Parses a message, also returning whatever text the matched parser left unconsumed.
+    /// For [`MessageType::Unknown`] the remainder is the whole input.
+    pub fn parse_with_remainder(i: &str) -> (Self, &str) {
+        match get_message_type(i) {
+            Ok((rest, m)) => (m, rest),
+            Err(_) => (MessageType::Unknown, i),
+        }
+    }
+
+    /// The game of a `Log file started` line
+    pub fn game(&self) -> Option<Game> {
+        match self {
+            Self::LogFileStarted { game, .. } => Some(Game::from(game.as_str())),
+            _ => None,
+        }
+    }
+
+    /// The server's address from either startup network line, [`MessageType::PublicIp`]
+    /// (`Public IP is x.x.x.x`) or [`MessageType::NetworkConfig`] (`Network: IP x.x.x.x, ...`)
+    pub fn server_ip(&self) -> Option<IpAddr> {
+        match self {
+            Self::PublicIp { ip } | Self::NetworkConfig { ip, .. } => Some(*ip),
+            _ => None,
+        }
+    }
+
+    /// The `(cp, cpname)` of a `pointcaptured` trigger, whether logged for the team or per player.
+    ///
+    /// `cp` is kept as logged, as payload and arena maps don't always number their points.
+    pub fn capture(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::TeamTriggered {
+                action, properties, ..
+            }
+            | Self::PlayerTriggered {
+                action, properties, ..
+            } if action == "pointcaptured" => {
+                Some((properties.get("cp")?, properties.get("cpname")?))
+            }
+            _ => None,
+        }
+    }
+
+    /// The `(playerN "<user>")` participants of an objective trigger such as `pointcaptured`,
+    /// each with its `positionN` / `playerN_position` if one was logged
+    pub fn objective_players(&self) -> Vec<(User, Option<Position>)> {
+        let properties = match self {
+            Self::TeamTriggered { properties, .. } | Self::PlayerTriggered { properties, .. } => {
+                properties
+            }
+            _ => return vec![],
+        };
+        (1..)
+            .map_while(|n| {
+                let user = properties.get(&format!("player{n}"))?;
+                let position = properties
+                    .get(&format!("position{n}"))
+                    .or_else(|| properties.get(&format!("player{n}_position")));
+                Some((user, position))
+            })
+            .filter_map(|(player, position)| {
+                let (_, user) = user(&format!("\"{player}\"")).ok()?;
+                let position = position
+                    .and_then(|p| parsers::position(p).ok())
+                    .map(|(_, p)| p);
+                Some((user, position))
+            })
+            .collect()
+    }
+
+    /// The player whose kill count this event changes, and by how much.
+    ///
+    /// A kill scores +1 for the attacker, while team kills and suicides (including killing
+    /// yourself) score -1.
+    pub fn kill_delta(&self) -> Option<(User, i32)> {
+        match self {
+            Self::Killed {
+                attacker, victim, ..
+            } => {
+                let self_kill = attacker.identity_key() == victim.identity_key();
+                let team_kill = !attacker.team.is_empty() && attacker.team == victim.team;
+                let delta = if self_kill || team_kill { -1 } else { 1 };
+                Some((attacker.clone(), delta))
+            }
+            Self::Suicide { user, .. } => Some((user.clone(), -1)),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a `player_carryobject` / `player_dropobject` player trigger
+    pub fn object_movement(&self) -> Option<ObjectMovement> {
+        match self {
+            Self::PlayerTriggered { action, .. } if action == "player_carryobject" => {
+                Some(ObjectMovement::CarryObject)
+            }
+            Self::PlayerTriggered { action, .. } if action == "player_dropobject" => {
+                Some(ObjectMovement::DropObject)
+            }
+            _ => None,
+        }
+    }
+
+    /// The `object` property of a player trigger, e.g. `OBJ_SENTRYGUN`
+    pub fn object(&self) -> Option<&str> {
+        match self {
+            Self::PlayerTriggered { properties, .. } => properties.get("object"),
+            _ => None,
+        }
+    }
+
+    /// The building of an `object_detonated`, `killedobject` or other player trigger with an
+    /// `object` property
+    pub fn building_type(&self) -> Option<BuildingType> {
+        match self {
+            Self::ObjectDetonated { object, .. } | Self::KilledObject { object, .. } => {
+                Some(object.clone())
+            }
+            _ => self.object().map(BuildingType::from),
+        }
+    }
+
+    /// Compares two messages structurally, treating users as equal when their steamid and uid
+    /// match regardless of display name or team
+    pub fn eq_by_identity(&self, other: &Self) -> bool {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        for user in a.users_mut().into_iter().chain(b.users_mut()) {
+            user.name.clear();
+            user.team.clear();
+        }
+        a == b
+    }
+
+    /// Anonymizes every user in the message, see [`User::anonymize`]
+    pub fn anonymize(&mut self, salt: &str) {
+        for user in self.users_mut() {
+            user.anonymize(salt);
+        }
+    }
+
+    /// Zeroes any IP addresses and drops any positions in the message
+    pub fn scrub_locations(&mut self) {
+        match self {
+            Self::Rcon { source, .. } => {
+                *source = RconSource::Ip(IpAddr::V4(Ipv4Addr::UNSPECIFIED), source.port())
+            }
+            Self::Connected { ip, .. } => *ip = Ipv4Addr::UNSPECIFIED,
+            Self::PublicIp { ip } | Self::NetworkConfig { ip, .. } => {
+                *ip = IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+            }
+            Self::Domination {
+                from_position,
+                against_position,
+                ..
+            }
+            | Self::Revenge {
+                from_position,
+                against_position,
+                ..
+            }
+            | Self::CsAttack {
+                attacker_position: from_position,
+                victim_position: against_position,
+                ..
+            } => {
+                *from_position = None;
+                *against_position = None;
+            }
+            Self::FlagEvent { position, .. } | Self::CaptureBlocked { position, .. } => {
+                *position = None
+            }
+            Self::PlayerPosition {
+                position, angles, ..
+            } => {
+                *position = Position { x: 0, y: 0, z: 0 };
+                *angles = None;
+            }
+            _ => (),
+        }
+    }
+
+    /// Every user referenced by this message
+    pub(crate) fn users_mut(&mut self) -> Vec<&mut User> {
+        match self {
+            Self::ChatMessage { from: user, .. }
+            | Self::Connected { user, .. }
+            | Self::Disconnected { user, .. }
+            | Self::JoinedTeam { user, .. }
+            | Self::PlayerTriggered { user, .. }
+            | Self::Suicide { user, .. }
+            | Self::ObjectDetonated { user, .. }
+            | Self::MoneyChange { user, .. }
+            | Self::BombEvent { user, .. }
+            | Self::FlagEvent { user, .. }
+            | Self::CapturedPoint { user, .. }
+            | Self::CaptureBlocked { user, .. }
+            | Self::PlayerPosition { user, .. }
+            | Self::PlayerSpawn { user, .. }
+            | Self::Accolade(Accolade { user, .. }) => vec![user],
+            Self::InterPlayerAction { from, against, .. }
+            | Self::Domination { from, against, .. }
+            | Self::Revenge { from, against, .. } => vec![from, against],
+            Self::CsAttack {
+                attacker, victim, ..
+            }
+            | Self::Damage {
+                attacker, victim, ..
+            }
+            | Self::Incapacitated { victim, attacker } => vec![attacker, victim],
+            Self::Revived { reviver, revived } => vec![reviver, revived],
+            Self::KilledObject {
+                attacker,
+                owner,
+                assister,
+                ..
+            } => [Some(attacker), owner.as_mut(), assister.as_mut()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            Self::Killed {
+                attacker,
+                victim,
+                assister,
+                ..
+            } => [Some(attacker), Some(victim), assister.as_mut()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Serializes into a compact binary form with `bincode`
+    #[cfg(feature = "bincode")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Deserializes from the binary form produced by [`MessageType::to_bytes`]
+    #[cfg(feature = "bincode")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(data)
+    }
+
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Self::LogFileStarted { .. } => MessageKind::LogFileStarted,
+            Self::LogFileClosed => MessageKind::LogFileClosed,
+            Self::ServerCvarsStart => MessageKind::ServerCvarsStart,
+            Self::ServerCvar { .. } => MessageKind::ServerCvar,
+            Self::ServerCvarsEnd => MessageKind::ServerCvarsEnd,
+            Self::LoadingMap { .. } => MessageKind::LoadingMap,
+            Self::StartedMap { .. } => MessageKind::StartedMap,
+            Self::Rcon { .. } => MessageKind::Rcon,
+            Self::ChatMessage { .. } => MessageKind::ChatMessage,
+            Self::Connected { .. } => MessageKind::Connected,
+            Self::Disconnected { .. } => MessageKind::Disconnected,
+            Self::JoinedTeam { .. } => MessageKind::JoinedTeam,
+            Self::InterPlayerAction { .. } => MessageKind::InterPlayerAction,
+            Self::PublicIp { .. } => MessageKind::PublicIp,
+            Self::NetworkConfig { .. } => MessageKind::NetworkConfig,
+            Self::ExecConfig { .. } => MessageKind::ExecConfig,
+            Self::PlayerIndexAssigned { .. } => MessageKind::PlayerIndexAssigned,
+            Self::BackendStatus { .. } => MessageKind::BackendStatus,
+            Self::ServerMessage { .. } => MessageKind::ServerMessage,
+            Self::BanListChange { .. } => MessageKind::BanListChange,
+            Self::Killed { .. } => MessageKind::Killed,
+            Self::Suicide { .. } => MessageKind::Suicide,
+            Self::Domination { .. } => MessageKind::Domination,
+            Self::Revenge { .. } => MessageKind::Revenge,
+            Self::MvmWave { .. } => MessageKind::MvmWave,
+            Self::TeamCurrentScore { .. } => MessageKind::TeamCurrentScore,
+            Self::TeamFinalScore { .. } => MessageKind::TeamFinalScore,
+            Self::PlayerTriggered { .. } => MessageKind::PlayerTriggered,
+            Self::TeamTriggered { .. } => MessageKind::TeamTriggered,
+            Self::WorldTriggered { .. } => MessageKind::WorldTriggered,
+            Self::MatchStatus { .. } => MessageKind::MatchStatus,
+            Self::Accolade(_) => MessageKind::Accolade,
+            Self::ObjectDetonated { .. } => MessageKind::ObjectDetonated,
+            Self::CsAttack { .. } => MessageKind::CsAttack,
+            Self::MoneyChange { .. } => MessageKind::MoneyChange,
+            Self::BombEvent { .. } => MessageKind::BombEvent,
+            Self::FlagEvent { .. } => MessageKind::FlagEvent,
+            Self::FatalError { .. } => MessageKind::FatalError,
+            Self::EngineError { .. } => MessageKind::EngineError,
+            Self::KilledObject { .. } => MessageKind::KilledObject,
+            Self::CapturedPoint { .. } => MessageKind::CapturedPoint,
+            Self::MapStatus { .. } => MessageKind::MapStatus,
+            Self::CaptureBlocked { .. } => MessageKind::CaptureBlocked,
+            Self::Damage { .. } => MessageKind::Damage,
+            Self::Reservation { .. } => MessageKind::Reservation,
+            Self::PlayerPosition { .. } => MessageKind::PlayerPosition,
+            Self::Diagnostic { .. } => MessageKind::Diagnostic,
+            Self::PlayerSpawn { .. } => MessageKind::PlayerSpawn,
+            Self::Incapacitated { .. } => MessageKind::Incapacitated,
+            Self::Revived { .. } => MessageKind::Revived,
+            Self::ServerShutdown => MessageKind::ServerShutdown,
+            Self::Unknown => MessageKind::Unknown,
+        }
+    }
+
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_unknown(&self) -> bool {
         match self {
             Self::Unknown => true,
             _ => false,
         }
     }
+
+    /// Whether this event changes the state of the game, for replay and undo indexes.
+    ///
+    /// - State changes: joins, leaves and team changes, map loads, kills, damage and other
+    ///   combat, dominations, triggers of every kind (captures, blocks, buildings, flags,
+    ///   bombs, waves, rounds) and money changes.
+    /// - Informational: log files, cvars, rcon, network and backend status, server messages,
+    ///   ban lists, errors, reservations and diagnostics, chat, position heartbeats, the map
+    ///   status and score reports that restate the current state, accolades, and anything
+    ///   unknown.
+    pub fn is_state_change(&self) -> bool {
+        use MessageKind::*;
+        match self.kind() {
+            Connected | Disconnected | JoinedTeam | LoadingMap | StartedMap => true,
+            Killed | CsAttack | Damage | Suicide | Domination | Revenge | InterPlayerAction => true,
+            PlayerTriggered | TeamTriggered | WorldTriggered | MvmWave | ObjectDetonated
+            | KilledObject | BombEvent | FlagEvent | CapturedPoint | CaptureBlocked
+            | PlayerSpawn | Incapacitated | Revived | MoneyChange => true,
+            LogFileStarted | LogFileClosed | ServerCvarsStart | ServerCvar | ServerCvarsEnd
+            | Rcon | PublicIp | NetworkConfig | ExecConfig | PlayerIndexAssigned
+            | BackendStatus | ServerMessage | BanListChange | FatalError | EngineError
+            | ServerShutdown | Reservation | Diagnostic => false,
+            ChatMessage | PlayerPosition | MapStatus | TeamCurrentScore | TeamFinalScore
+            | MatchStatus | Accolade | Unknown => false,
+        }
+    }
+
+    /// The winning team of a `Round_Win` / `Round_Setup_End` world trigger
+    pub fn round_winner(&self) -> Option<&str> {
+        match self {
+            Self::WorldTriggered { action, properties }
+                if action == "Round_Win" || action == "Round_Setup_End" =>
+            {
+                properties.get("winner")
+            }
+            _ => None,
+        }
+    }
+
+    /// The text of a chat message with any color codes removed, for display.
+    ///
+    /// Strips `\x07RRGGBB` and `\x08RRGGBBAA` colors and the other control bytes, like `\x01`
+    /// and `\x03`, that select preset colors. The raw text is left as is in the variant.
+    pub fn chat_text_plain(&self) -> Option<String> {
+        let Self::ChatMessage { message, .. } = self else {
+            return None;
+        };
+        let mut plain = String::with_capacity(message.len());
+        let mut chars = message.chars();
+        while let Some(c) = chars.next() {
+            let hex_digits = match c {
+                '\x07' => 6,
+                '\x08' => 8,
+                c if c.is_control() => 0,
+                c => {
+                    plain.push(c);
+                    continue;
+                }
+            };
+            // only skip a color that is actually there
+            let color = chars.as_str().get(..hex_digits).unwrap_or_default();
+            if color.len() == hex_digits && color.chars().all(|c| c.is_ascii_hexdigit()) {
+                chars = chars.as_str()[hex_digits..].chars();
+            }
+        }
+        Some(plain)
+    }
+
+    /// The team an event pertains to, e.g. for per-team heatmaps.
+    ///
+    /// This is the joined team of a `JoinedTeam`, the winner of a round win, the team of a team
+    /// trigger or score, and otherwise the team of the acting user: the attacker of a kill or the
+    /// `from` user of a domination, revenge or other action against another player.
+    pub fn affected_team(&self) -> Option<&str> {
+        match self {
+            Self::JoinedTeam { team, .. }
+            | Self::TeamTriggered { team, .. }
+            | Self::TeamCurrentScore { team, .. }
+            | Self::TeamFinalScore { team, .. } => Some(team),
+            Self::WorldTriggered { .. } => self.round_winner(),
+            Self::ChatMessage { from: user, .. }
+            | Self::Connected { user, .. }
+            | Self::Disconnected { user, .. }
+            | Self::InterPlayerAction { from: user, .. }
+            | Self::Killed { attacker: user, .. }
+            | Self::CsAttack { attacker: user, .. }
+            | Self::Damage { attacker: user, .. }
+            | Self::KilledObject { attacker: user, .. }
+            | Self::Suicide { user, .. }
+            | Self::Domination { from: user, .. }
+            | Self::Revenge { from: user, .. }
+            | Self::PlayerTriggered { user, .. }
+            | Self::ObjectDetonated { user, .. }
+            | Self::MoneyChange { user, .. }
+            | Self::BombEvent { user, .. }
+            | Self::FlagEvent { user, .. }
+            | Self::CapturedPoint { user, .. }
+            | Self::CaptureBlocked { user, .. }
+            | Self::PlayerPosition { user, .. }
+            | Self::PlayerSpawn { user, .. }
+            | Self::Incapacitated { victim: user, .. }
+            | Self::Revived { reviver: user, .. } => Some(&user.team),
+            _ => None,
+        }
+    }
+
+    /// Seconds from spawn until a medic's first heal, from `first_heal_after_spawn`
+    pub fn first_heal_time(&self) -> Option<f32> {
+        match self {
+            Self::PlayerTriggered {
+                action, properties, ..
+            } if action == "first_heal_after_spawn" => properties.get("time")?.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether a combat event was a crit or minicrit, from its `crit` property
+    pub fn crit_kind(&self) -> Option<CritKind> {
+        match self {
+            Self::Damage {
+                crit: Some(crit), ..
+            } => match crit.as_str() {
+                "crit" => Some(CritKind::Crit),
+                "minicrit" => Some(CritKind::MiniCrit),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether a combat event was a crit or minicrit
+    pub fn is_crit(&self) -> bool {
+        self.crit_kind().is_some()
+    }
+
+    /// The `(cp, capteam)` of a KOTH `koth_point_captured` world trigger
+    pub fn koth_capture(&self) -> Option<(u32, u32)> {
+        match self {
+            Self::WorldTriggered { action, properties } if action == "koth_point_captured" => {
+                let cp = properties.get("cp")?.parse().ok()?;
+                let capteam = properties.get("capteam")?.parse().ok()?;
+                Some((cp, capteam))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&LogMessage> for MessageType {
+    fn from(msg: &LogMessage) -> Self {
+        Self::from_log_message(msg)
+    }
 }
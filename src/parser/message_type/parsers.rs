@@ -1,300 +1,2714 @@
-use super::{MessageType, User};
-use nom::{branch::Alt, Err};
-use regex::Regex;
+use super::{
+    Accolade, Angles, BackendEvent, BombAction, BuildingType, FlagEventKind, MessageType,
+    MvmWaveEvent, ParserConfig, Position, Properties, RconSource, Team, TfClass, User,
+};
+use nom::branch::Alt;
 
 #[allow(unused_imports)]
 use nom::{
     bytes::complete::{tag, tag_no_case, take_until, take_until1, take_while, take_while1},
     character::{
-        complete::{alpha0, char, digit1},
+        complete::{alpha0, char, digit1, multispace0, satisfy},
         is_space,
     },
-    combinator::fail,
+    combinator::{fail, opt, recognize},
     error,
-    multi::{many0_count, many1},
-    sequence::{delimited, preceded, Tuple},
+    multi::{many0, many0_count, many1},
+    number::complete::float,
+    sequence::{delimited, pair, preceded, tuple, Tuple},
     IResult, Parser,
 };
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
 
 pub fn get_message_type(i: &str) -> IResult<&str, MessageType> {
+    get_message_type_with_config(i, &ParserConfig::default())
+}
+
+/// Runs only the parser groups enabled in `config`
+pub fn get_message_type_with_config<'a>(
+    i: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, MessageType> {
+    type EventParser = fn(&str) -> IResult<&str, MessageType>;
+    let groups: [(bool, EventParser); 6] = [
+        (config.server, server_event),
+        (config.chat, chat_event),
+        (config.connections, connection_event),
+        (config.kills, kill_event),
+        (config.triggers, trigger_event),
+        (config.scores, score_event),
+    ];
+    for (_, parser) in groups.into_iter().filter(|(enabled, _)| *enabled) {
+        if let Ok(res) = parser(i) {
+            return Ok(res);
+        }
+    }
+    fail(i)
+}
+
+pub fn server_event(i: &str) -> IResult<&str, MessageType> {
     log_file_started
         .or(log_file_closed)
         .or(server_cvars_start)
         .or(server_cvars_end)
+        .or(server_cvar)
         .or(loading_map)
         .or(starting_map)
+        .or(map_status)
         .or(rcon)
-        .or(chat_message)
-        .or(connect_message)
+        .or(public_ip)
+        .or(network_config)
+        .or(exec_config)
+        .or(player_index_assigned)
+        .or(server_message)
+        .or(ban_list_change)
+        .or(backend_status)
+        .or(fatal_error)
+        .or(engine_error)
+        .or(reservation)
+        .or(engine_diagnostic)
+        .or(server_shutdown)
+        .parse(i)
+}
+
+pub fn fatal_error(i: &str) -> IResult<&str, MessageType> {
+    let (rest, marker) = (
+        tag("Host_Error:"),
+        tag("FATAL ERROR"),
+        tag("Segmentation fault"),
+        // only the engine's own assert markers, so chat-like text such as "asserting" is not fatal
+        tag("Assert("),
+        tag("ASSERT:"),
+        tag_no_case("Assertion Failed"),
+    )
+        .choice(i)?;
+    // segfaults and asserts have no message of their own, so the whole line is kept
+    let text = match marker {
+        "Host_Error:" | "FATAL ERROR" => rest.strip_prefix(':').unwrap_or(rest),
+        _ => i,
+    };
+    Ok((
+        "",
+        MessageType::FatalError {
+            text: text.trim().to_owned(),
+        },
+    ))
+}
+
+pub fn engine_error(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = (tag_no_case("error"), tag_no_case("warning")).choice(i)?;
+    let (text, _) = char(':')(i)?;
+    Ok((
+        "",
+        MessageType::EngineError {
+            text: text.trim().to_owned(),
+        },
+    ))
+}
+
+pub fn reservation(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = opt(tag("-> "))(i)?;
+    let (i, _) = tag_no_case("Reservation cookie ")(i)?;
+    let (i, cookie) = take_while1(|c: char| c.is_ascii_alphanumeric())(i)?;
+    Ok((
+        i,
+        MessageType::Reservation {
+            cookie: cookie.to_owned(),
+        },
+    ))
+}
+
+pub fn engine_diagnostic(i: &str) -> IResult<&str, MessageType> {
+    (tag_no_case("server frame"), tag_no_case("fps drop")).choice(i)?;
+    Ok((
+        "",
+        MessageType::Diagnostic {
+            text: i.trim_end().to_owned(),
+        },
+    ))
+}
+
+pub fn server_shutdown(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = (
+        tag_no_case("server shutting down"),
+        tag_no_case("server is shutting down"),
+        tag_no_case("quit"),
+    )
+        .choice(i)?;
+    let (i, _) = multispace0(i)?;
+    if !i.is_empty() {
+        return fail(i);
+    }
+    Ok((i, MessageType::ServerShutdown))
+}
+
+pub fn backend_status(i: &str) -> IResult<&str, MessageType> {
+    let (i, event) = (
+        tag_no_case("connection to steam servers successful").map(|_| BackendEvent::SteamConnected),
+        tag_no_case("connection to steam servers lost").map(|_| BackendEvent::SteamDisconnected),
+        tag_no_case("lost connection to steam servers").map(|_| BackendEvent::SteamDisconnected),
+        tag_no_case("connection to game coordinator established")
+            .map(|_| BackendEvent::GcConnected),
+        tag_no_case("connection to game coordinator lost").map(|_| BackendEvent::GcDisconnected),
+        tag_no_case("lost connection to game coordinator").map(|_| BackendEvent::GcDisconnected),
+    )
+        .choice(i)?;
+    let (i, _) = opt(char('.'))(i)?;
+    Ok((i, MessageType::BackendStatus { event }))
+}
+
+pub fn ban_list_change(i: &str) -> IResult<&str, MessageType> {
+    let (i, action) = (
+        tag_no_case("addip"),
+        tag_no_case("removeip"),
+        tag_no_case("banid"),
+        tag_no_case("removeid"),
+    )
+        .choice(i)?;
+    let (i, args) = preceded(char(' '), take_while1(|c| c != '\n'))(i)?;
+    let Some(target) = args.split_whitespace().last() else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::BanListChange {
+            action: action.to_lowercase(),
+            target: target.trim_matches('"').to_owned(),
+        },
+    ))
+}
+
+pub fn chat_event(i: &str) -> IResult<&str, MessageType> {
+    chat_message(i)
+}
+
+pub fn connection_event(i: &str) -> IResult<&str, MessageType> {
+    connect_message
         .or(disconnect_message)
-        .or(inter_player_action)
         .or(join_team_msg)
         .parse(i)
 }
 
-pub fn rcon(i: &str) -> IResult<&str, MessageType> {
-    let (i, _) = tag_no_case("rcon from ").parse(i)?;
-    let (i, (ip, port)) = delimited(char('"'), ipv4_with_port, char('"'))(i)?;
-    let (i, _) = tag(": command ")(i)?;
-    let (i, command) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+pub fn kill_event(i: &str) -> IResult<&str, MessageType> {
+    kill_message
+        .or(cs_attack)
+        .or(suicide_message)
+        .or(domination_message)
+        .or(damage_event)
+        .parse(i)
+}
+
+pub fn trigger_event(i: &str) -> IResult<&str, MessageType> {
+    survivor_action
+        .or(inter_player_action)
+        .or(object_detonated)
+        .or(killed_object)
+        .or(bomb_event)
+        .or(flag_event)
+        .or(captured_point)
+        .or(capture_blocked)
+        .or(player_position)
+        .or(player_spawn)
+        .or(player_triggered)
+        .or(team_triggered)
+        .or(mvm_wave)
+        .or(world_triggered)
+        .parse(i)
+}
+
+pub fn score_event(i: &str) -> IResult<&str, MessageType> {
+    team_score
+        .or(match_status)
+        .or(accolade)
+        .or(money_change)
+        .parse(i)
+}
+
+pub fn match_status(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("matchstatus: score: ")(i)?;
+    let (i, (ct_score, _, t_score)) = (digit1, char(':'), digit1).parse(i)?;
+    let (i, _) = tag(" on map ")(i)?;
+    let (i, map) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, _) = tag(" RoundsPlayed: ")(i)?;
+    let (i, rounds) = digit1(i)?;
+
+    let (Ok(ct_score), Ok(t_score), Ok(rounds)) =
+        (ct_score.parse(), t_score.parse(), rounds.parse())
+    else {
+        return fail(i);
+    };
     Ok((
         i,
-        MessageType::Rcon {
-            ip,
-            port,
-            command: command.to_owned(),
+        MessageType::MatchStatus {
+            ct_score,
+            t_score,
+            map: map.to_owned(),
+            rounds,
         },
     ))
 }
 
-pub fn log_file_closed(i: &str) -> IResult<&str, MessageType> {
-    let _ = tag_no_case("log file closed")(i)?;
-    Ok((i, MessageType::LogFileClosed))
-}
+/// `ACCOLADE, FINAL: {3k},\tname<uid>,\tVALUE: 2.000000,\tPOS: 1,\tSCORE: 40.000000`
+pub fn accolade(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("accolade, final: ")(i)?;
+    let (i, category) = delimited(char('{'), take_until1("}"), char('}'))(i)?;
+    let (i, _) = pair(char(','), multispace0)(i)?;
+    let (i, name_uid) = take_until1(">,")(i)?;
+    let (i, _) = pair(tag(">,"), multispace0)(i)?;
+    let (i, value) = preceded(tag("VALUE: "), float)(i)?;
+    let (i, _) = pair(char(','), multispace0)(i)?;
+    let (i, position) = preceded(tag("POS: "), digit1)(i)?;
+    let (i, _) = pair(char(','), multispace0)(i)?;
+    let (i, score) = preceded(tag("SCORE: "), float)(i)?;
 
-pub fn server_cvars_start(i: &str) -> IResult<&str, MessageType> {
-    let _ = tag_no_case("server cvars start")(i)?;
-    Ok((i, MessageType::ServerCvarsStart))
+    let Some((name, uid)) = name_uid.rsplit_once('<') else {
+        return fail(i);
+    };
+    let (Ok(uid), Ok(position)) = (uid.parse(), position.parse()) else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::Accolade(Accolade {
+            category: category.to_owned(),
+            user: User {
+                name: name.to_owned(),
+                uid,
+                steamid: String::new(),
+                team: String::new(),
+            },
+            value,
+            position,
+            score,
+        }),
+    ))
 }
 
-pub fn server_cvars_end(i: &str) -> IResult<&str, MessageType> {
-    let _ = tag_no_case("server cvars end")(i)?;
-    Ok((i, MessageType::ServerCvarsEnd))
+/// `"<user>" money change 16000-2700 = $13300 (tracked) (purchase: weapon_ak47)`
+pub fn money_change(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" money change ")(i)?;
+    let (i, before) = signed_int(i)?;
+    let (i, (sign, amount)) = pair(char('+').or(char('-')), digit1)(i)?;
+    let (i, _) = tag(" = $")(i)?;
+    let (i, after) = signed_int(i)?;
+    let (i, _) = opt(tag(" (tracked)"))(i)?;
+    let (i, purchase) = opt(delimited(tag(" (purchase: "), take_until1(")"), char(')')))(i)?;
+
+    let Ok(amount) = amount.parse::<i32>() else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::MoneyChange {
+            user,
+            before,
+            delta: if sign == '-' { -amount } else { amount },
+            after,
+            purchase: purchase.map(str::to_owned),
+        },
+    ))
 }
 
-pub fn loading_map(i: &str) -> IResult<&str, MessageType> {
-    let (i, _) = tag("loading map ")(i)?;
-    let (i, name) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+pub fn server_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("server message: ")(i)?;
+    let (i, text) = delimited(char('"'), take_until("\""), char('"'))(i)?;
     Ok((
         i,
-        MessageType::LoadingMap {
-            name: name.to_owned(),
+        MessageType::ServerMessage {
+            text: text.to_owned(),
         },
     ))
 }
 
-pub fn starting_map(i: &str) -> IResult<&str, MessageType> {
-    let (i, _) = tag_no_case("started map ")(i)?;
+pub fn player_index_assigned(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("assigning unique id ")(i)?;
+    let (i, index) = digit1(i)?;
+    let (i, _) = tag(" to ")(i)?;
     let (i, name) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
-    let (i, _) = take_while(char::is_whitespace)(i)?;
-    let (i, (_, crc)) = kv_pair(i)?;
+    let Ok(index) = index.parse() else {
+        return fail(i);
+    };
     Ok((
         i,
-        MessageType::StartedMap {
+        MessageType::PlayerIndexAssigned {
             name: name.to_owned(),
-            crc: crc.to_owned(),
+            index,
         },
     ))
 }
 
-pub fn log_file_started(i: &str) -> IResult<&str, MessageType> {
-    let (i, _) = tag_no_case("log file started ")(i)?;
-    let (i, (_, file)) = kv_pair(i)?;
-    let (i, _) = take_while1(char::is_whitespace)(i)?;
-    let (i, (_, game)) = kv_pair(i)?;
-    let (i, _) = take_while1(char::is_whitespace)(i)?;
-    let (i, (_, version)) = kv_pair(i)?;
-
+pub fn exec_config(i: &str) -> IResult<&str, MessageType> {
+    let (i, prefix) = (
+        tag_no_case("executing dedicated server config file "),
+        tag_no_case("exec: couldn't exec "),
+    )
+        .choice(i)?;
+    let (i, file) = take_while1(|c: char| !c.is_whitespace())(i)?;
     Ok((
         i,
-        MessageType::LogFileStarted {
+        MessageType::ExecConfig {
             file: file.to_owned(),
-            game: game.to_owned(),
-            version: version.to_owned(),
+            success: !prefix.starts_with("exec:"),
         },
     ))
 }
 
-pub fn kv_pair<'a>(i: &'a str) -> IResult<&'a str, (&'a str, &'a str)> {
-    delimited(
-        char('('),
-        |i: &'a str| {
-            (
-                take_until(" "),
-                preceded(char(' '), delimited(char('"'), take_until("\""), char('"'))),
-            )
-                .parse(i)
+pub fn public_ip(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("public ip is ")(i)?;
+    let (i, ip) = ipv4(i)?;
+    let (i, _) = opt(char('.'))(i)?;
+    Ok((i, MessageType::PublicIp { ip: IpAddr::V4(ip) }))
+}
+
+pub fn network_config(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("network: ip ")(i)?;
+    let (i, ip) = ipv4(i)?;
+    let (i, _) = take_until(", ports ")(i)?;
+    let (i, _) = tag(", ports ")(i)?;
+    let (i, sv_port) = port(i)?;
+    let (i, _) = tag(" SV / ")(i)?;
+    let (i, cl_port) = port(i)?;
+    let (i, _) = tag(" CL")(i)?;
+    Ok((
+        i,
+        MessageType::NetworkConfig {
+            ip: IpAddr::V4(ip),
+            sv_port,
+            cl_port,
         },
-        char(')'),
-    )
-    .parse(i)
+    ))
 }
 
-pub fn join_team_msg(i: &str) -> IResult<&str, MessageType> {
-    let (i, user) = user(i)?;
-    let (i, _) = tag(" joined team ")(i)?;
+pub fn team_score(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("team ")(i)?;
     let (i, team) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    // CS logs the running score as `scored`
+    let (i, kind) = (
+        tag(" current score "),
+        tag(" scored "),
+        tag(" final score "),
+    )
+        .choice(i)?;
+    let (i, score) = delimited(char('"'), digit1, char('"'))(i)?;
+    let (i, _) = tag(" with ")(i)?;
+    let (i, players) = delimited(char('"'), digit1, char('"'))(i)?;
+    let (i, _) = tag(" players")(i)?;
+
+    let (Ok(score), Ok(players)) = (score.parse(), players.parse()) else {
+        return fail(i);
+    };
+    let team = team.to_owned();
     Ok((
         i,
-        MessageType::JoinedTeam {
-            user,
-            team: team.to_owned(),
+        if kind != " final score " {
+            MessageType::TeamCurrentScore {
+                team,
+                score,
+                players,
+            }
+        } else {
+            MessageType::TeamFinalScore {
+                team,
+                score,
+                players,
+            }
         },
     ))
 }
 
-pub fn inter_player_action(i: &str) -> IResult<&str, MessageType> {
-    let (i, from) = user(i)?;
+pub fn team_triggered(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("team ")(i)?;
+    let (i, team) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
     let (i, _) = tag_no_case(" triggered ")(i)?;
     let (i, action) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
-    let (i, _) = tag_no_case(" against ")(i)?;
-    let (i, against) = user(i)?;
-
+    let (i, properties) = properties(i)?;
     Ok((
         i,
-        MessageType::InterPlayerAction {
-            from,
+        MessageType::TeamTriggered {
+            team: team.to_owned(),
             action: action.to_owned(),
-            against,
+            properties,
         },
     ))
 }
 
-pub fn ipv4_with_port(i: &str) -> IResult<&str, (Ipv4Addr, u16)> {
-    let (i, ip) = ipv4(i)?;
-    let (i, _) = char(':')(i)?;
-    let (i, port) = port(i)?;
-    Ok((i, (ip, port)))
+pub fn mvm_wave(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("world triggered \"mann vs machine event\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let event = match properties.get("event") {
+        Some("wave_start") => MvmWaveEvent::WaveStart,
+        Some("wave_complete") => MvmWaveEvent::WaveComplete,
+        Some("wave_failed") => MvmWaveEvent::WaveFailed,
+        Some("mission_complete") => MvmWaveEvent::MissionComplete,
+        _ => return fail(i),
+    };
+    let Some(Ok(wave)) = properties.get("wave").map(str::parse) else {
+        return fail(i);
+    };
+    Ok((i, MessageType::MvmWave { event, wave }))
 }
 
-pub fn port(i: &str) -> IResult<&str, u16> {
-    let (i, port) = digit1(i)?;
-    if let Ok(port) = port.parse::<u16>() {
-        Ok((i, port))
-    } else {
-        fail(i)
-    }
+pub fn object_detonated(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" triggered \"object_detonated\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let Some(object) = properties.get("object") else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::ObjectDetonated {
+            user,
+            object: BuildingType::from(object),
+        },
+    ))
 }
 
-pub fn ipv4(i: &str) -> IResult<&str, Ipv4Addr> {
-    let (i, (a, _, b, _, c, _, d)) = (
-        digit1,
-        char('.'),
-        digit1,
-        char('.'),
-        digit1,
-        char('.'),
-        digit1,
-    )
-        .parse(i)?;
-
+pub fn player_spawn(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" triggered \"player_spawn\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let Some(class) = properties.get("class") else {
+        return fail(i);
+    };
+    let team = properties.get("team").unwrap_or(&user.team);
     Ok((
         i,
-        Ipv4Addr::new(
-            a.parse().unwrap(),
-            b.parse().unwrap(),
-            c.parse().unwrap(),
-            d.parse().unwrap(),
-        ),
+        MessageType::PlayerSpawn {
+            class: TfClass::from(class),
+            team: Team::from(team),
+            user,
+        },
     ))
 }
 
-pub fn user(i: &str) -> IResult<&str, User> {
-    let re = Regex::new(r#""(.*?)<(\d+)><(\[U:\d:\d+\])><(\w*)?>""#).unwrap();
-    let Some(caps) = re.captures(i) else {
-        return Err(Err::Error(nom::error::Error::new(
-            i,
-            nom::error::ErrorKind::Tag,
-        )));
+pub fn bomb_event(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" triggered ")(i)?;
+    let (i, action) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let event = match action {
+        "Got_The_Bomb" => BombAction::Picked,
+        "Dropped_The_Bomb" => BombAction::Dropped,
+        "Planted_The_Bomb" => BombAction::Planted,
+        "Began_Bomb_Defuse_With_Kit" => BombAction::BeganDefuse { with_kit: true },
+        "Began_Bomb_Defuse_Without_Kit" => BombAction::BeganDefuse { with_kit: false },
+        "Defused_The_Bomb" => BombAction::Defused,
+        _ => return fail(i),
     };
+    let (i, _) = properties(i)?;
+    Ok((i, MessageType::BombEvent { user, event }))
+}
 
-    let len = caps.get(0).unwrap().len();
-    let name = caps.get(1).unwrap().as_str();
-    let uid = caps.get(2).unwrap().as_str();
-    let steamid = caps.get(3).unwrap().as_str();
-    let team = caps.get(4).unwrap().as_str();
+pub fn flag_event(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" triggered \"flagevent\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let event = match properties.get("event") {
+        Some("picked up") => FlagEventKind::PickedUp,
+        Some("dropped") => FlagEventKind::Dropped,
+        Some("captured") => FlagEventKind::Captured,
+        Some("defended") => FlagEventKind::Defended,
+        _ => return fail(i),
+    };
+    Ok((
+        i,
+        MessageType::FlagEvent {
+            user,
+            event,
+            position: properties.get_position("position"),
+        },
+    ))
+}
 
+pub fn killed_object(i: &str) -> IResult<&str, MessageType> {
+    let (i, attacker) = user(i)?;
+    let (i, _) = tag(" triggered \"killedobject\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let Some(object) = properties.get("object") else {
+        return fail(i);
+    };
+    // the owner is a pseudo-user like `World` rather than a player when nobody owned the building
+    let owner = property_user(&properties, "objectowner");
     Ok((
-        &i[len..],
-        User {
-            name: name.to_owned(),
-            uid: uid.parse().unwrap(),
-            steamid: steamid.to_owned(),
-            team: team.to_owned(),
+        i,
+        MessageType::KilledObject {
+            attacker,
+            object: BuildingType::from(object),
+            weapon: properties.get("weapon").map(str::to_owned),
+            owner,
+            assister: property_user(&properties, "assist"),
         },
     ))
 }
 
-pub fn disconnect_message(i: &str) -> IResult<&str, MessageType> {
+pub fn captured_point(i: &str) -> IResult<&str, MessageType> {
     let (i, user) = user(i)?;
-    let (i, _) = tag(" disconnected (reason ")(i)?;
-    let (i, reason) = delimited(char('"'), take_until1("\""), tag("\")")).parse(i)?;
+    let (i, _) = tag(" triggered \"captured_point\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let Some(point) = properties.get("point") else {
+        return fail(i);
+    };
     Ok((
         i,
-        MessageType::Disconnected {
+        MessageType::CapturedPoint {
             user,
-            reason: reason.to_owned(),
+            point: point.to_owned(),
         },
     ))
 }
 
-pub fn connect_message(i: &str) -> IResult<&str, MessageType> {
+pub fn capture_blocked(i: &str) -> IResult<&str, MessageType> {
     let (i, user) = user(i)?;
-    let (i, _) = tag(" connected, address ")(i)?;
-    let (i, (ip, port)) = delimited(char('"'), ipv4_with_port, char('"')).parse(i)?;
-    Ok((i, MessageType::Connected { user, ip, port }))
+    let (i, inline_position) = opt(bracket_position)(i)?;
+    let (i, _) = tag(" triggered \"captureblocked\"")(i)?;
+    let (i, properties) = properties(i)?;
+    let (Some(cp), Some(cpname)) = (properties.get("cp"), properties.get("cpname")) else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::CaptureBlocked {
+            user,
+            cp: cp.to_owned(),
+            cpname: cpname.to_owned(),
+            position: inline_position.or_else(|| properties.get_position("position")),
+            properties,
+        },
+    ))
 }
 
-pub fn chat_message(i: &str) -> IResult<&str, MessageType> {
+pub fn player_position(i: &str) -> IResult<&str, MessageType> {
     let (i, user) = user(i)?;
-    let (i, say) = (tag(" say "), tag(" say_team ")).choice(i)?;
-    let (i, message) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, position) = delimited(tag(" position \""), position, char('"'))(i)?;
+    let (i, angles) = opt(delimited(
+        tag(" angles \""),
+        tuple((float, char(' '), float, char(' '), float)),
+        char('"'),
+    ))(i)?;
+    Ok((
+        i,
+        MessageType::PlayerPosition {
+            user,
+            position,
+            angles: angles.map(|(pitch, _, yaw, _, roll)| Angles { pitch, yaw, roll }),
+        },
+    ))
+}
 
+pub fn world_triggered(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("world triggered ")(i)?;
+    let (i, action) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, properties) = properties(i)?;
     Ok((
         i,
-        MessageType::ChatMessage {
-            from: user,
-            message: message.to_owned(),
-            team: say == " say_team ",
+        MessageType::WorldTriggered {
+            action: action.to_owned(),
+            properties,
         },
     ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub fn rcon(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("rcon from ").parse(i)?;
+    let (i, source) = delimited(char('"'), rcon_source, char('"'))(i)?;
+    let (i, _) = tag(": command ")(i)?;
+    let (i, command) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    Ok((
+        i,
+        MessageType::Rcon {
+            source,
+            command: command.to_owned(),
+        },
+    ))
+}
 
-    #[test]
-    fn connect_message() {
-        const LINE: &str =
-            "\"User<1><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"";
-        let parsed = get_message_type(LINE).unwrap();
-        dbg!(&parsed);
-        match parsed.1 {
-            MessageType::Connected { .. } => (),
-            _ => panic!("grr"),
-        }
+/// `1.2.3.4:27015`, falling back to `hostname:27015`
+fn rcon_source(i: &str) -> IResult<&str, RconSource> {
+    if let Ok((i, (ip, port))) = ipv4_with_port(i) {
+        return Ok((i, RconSource::Ip(IpAddr::V4(ip), port)));
     }
+    let (i, host) = take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '.')(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, port) = port(i)?;
+    Ok((i, RconSource::Host(host.to_owned(), port)))
+}
 
-    #[test]
+pub fn log_file_closed(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("log file closed")(i)?;
+    Ok((i, MessageType::LogFileClosed))
+}
+
+pub fn server_cvars_start(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("server cvars start")(i)?;
+    Ok((i, MessageType::ServerCvarsStart))
+}
+
+pub fn server_cvars_end(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("server cvars end")(i)?;
+    Ok((i, MessageType::ServerCvarsEnd))
+}
+
+pub fn server_cvar(i: &str) -> IResult<&str, MessageType> {
+    let quoted = || delimited(char('"'), take_until("\""), char('"'));
+    let (i, (var, value)) = preceded(
+        tag_no_case("server_cvar: "),
+        pair(quoted(), preceded(char(' '), quoted())),
+    )
+    .or(pair(quoted(), preceded(tag(" = "), quoted())))
+    .parse(i)?;
+    let (i, limits) = opt(delimited(
+        tag(" ( def. "),
+        tuple((
+            quoted(),
+            opt(preceded(tag(" min. "), quoted())),
+            opt(preceded(tag(" max. "), quoted())),
+        )),
+        tag(" )"),
+    ))(i)?;
+    if var.is_empty() {
+        return fail(i);
+    }
+
+    let (default, min, max) = match limits {
+        Some((default, min, max)) => (Some(default), min, max),
+        None => (None, None, None),
+    };
+    Ok((
+        i,
+        MessageType::ServerCvar {
+            var: var.to_owned(),
+            value: value.to_owned(),
+            default: default.map(str::to_owned),
+            min: min.map(str::to_owned),
+            max: max.map(str::to_owned),
+        },
+    ))
+}
+
+pub fn map_status(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = (tag("Map: "), tag_no_case("changelevel ")).choice(i)?;
+    let (i, name) = take_while1(|c: char| !c.is_whitespace())(i)?;
+    Ok((
+        i,
+        MessageType::MapStatus {
+            name: name.to_owned(),
+        },
+    ))
+}
+
+pub fn loading_map(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("loading map ")(i)?;
+    let (i, name) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, properties) = properties(i)?;
+    Ok((
+        i,
+        MessageType::LoadingMap {
+            name: name.to_owned(),
+            properties,
+        },
+    ))
+}
+
+pub fn starting_map(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("started map ")(i)?;
+    let (i, name) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, crc) = opt(preceded(take_while(char::is_whitespace), kv_pair))(i)?;
+    Ok((
+        i,
+        MessageType::StartedMap {
+            name: name.to_owned(),
+            crc: crc.map(|(_, crc)| crc.to_owned()),
+        },
+    ))
+}
+
+pub fn log_file_started(i: &str) -> IResult<&str, MessageType> {
+    let (i, _) = tag_no_case("log file started ")(i)?;
+    let (i, (_, file)) = kv_pair(i)?;
+    let (i, _) = take_while1(char::is_whitespace)(i)?;
+    let (i, (_, game)) = kv_pair(i)?;
+    let (i, _) = take_while1(char::is_whitespace)(i)?;
+    let (i, (_, version)) = kv_pair(i)?;
+
+    Ok((
+        i,
+        MessageType::LogFileStarted {
+            file: file.to_owned(),
+            game: game.to_owned(),
+            version: version.to_owned(),
+        },
+    ))
+}
+
+pub fn kv_pair<'a>(i: &'a str) -> IResult<&'a str, (&'a str, &'a str)> {
+    delimited(
+        char('('),
+        |i: &'a str| {
+            let (i, key) = take_while1(|c: char| !c.is_whitespace() && c != '"' && c != ')')(i)?;
+            let (i, _) = take_while(|c| c == ' ')(i)?;
+            // the value ends at the `")` closing the pair, so backslashes and stray quotes in
+            // it, as in Windows paths, are kept as they are
+            let (i, value) = delimited(char('"'), take_until("\")"), char('"'))(i)?;
+            Ok((i, (key, value)))
+        },
+        char(')'),
+    )
+    .parse(i)
+}
+
+/// Any number of trailing ` (key "value")` pairs
+pub fn properties(i: &str) -> IResult<&str, Properties> {
+    many0(preceded(take_while(char::is_whitespace), kv_pair))
+        .map(|pairs| {
+            Properties(
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect(),
+            )
+        })
+        .parse(i)
+}
+
+pub fn join_team_msg(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" joined team ")(i)?;
+    let (i, team) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    Ok((
+        i,
+        MessageType::JoinedTeam {
+            user,
+            team: team.to_owned(),
+        },
+    ))
+}
+
+pub fn survivor_action(i: &str) -> IResult<&str, MessageType> {
+    let (i, from) = user(i)?;
+    let (i, action) = (
+        tag(" triggered \"incapacitated\""),
+        tag(" triggered \"revived\""),
+    )
+        .choice(i)?;
+    let (i, _) = tag(" against ")(i)?;
+    let (i, against) = user(i)?;
+    let (i, _) = properties(i)?;
+    Ok((
+        i,
+        if action.ends_with("\"incapacitated\"") {
+            MessageType::Incapacitated {
+                victim: from,
+                attacker: against,
+            }
+        } else {
+            MessageType::Revived {
+                reviver: from,
+                revived: against,
+            }
+        },
+    ))
+}
+
+pub fn inter_player_action(i: &str) -> IResult<&str, MessageType> {
+    let (i, from) = user(i)?;
+    let (i, _) = tag_no_case(" triggered ")(i)?;
+    let (i, action) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, _) = tag_no_case(" against ")(i)?;
+    let (i, against) = user(i)?;
+    let (i, properties) = properties(i)?;
+
+    Ok((
+        i,
+        MessageType::InterPlayerAction {
+            from,
+            action: action.to_owned(),
+            against,
+            properties,
+        },
+    ))
+}
+
+pub fn kill_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, attacker) = user(i)?;
+    let (i, _) = opt(bracket_position)(i)?;
+    let (i, _) = tag(" killed ")(i)?;
+    let (i, victim) = user(i)?;
+    let (i, _) = opt(bracket_position)(i)?;
+    let (i, _) = tag(" with ")(i)?;
+    let (i, weapon) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, assister) = opt(delimited(tag(" (assist "), user, char(')')))(i)?;
+    Ok((
+        i,
+        MessageType::Killed {
+            attacker,
+            victim,
+            weapon: weapon.to_owned(),
+            assister,
+        },
+    ))
+}
+
+/// `"<user>" [x y z] attacked "<user>" [x y z] with "<weapon>" (damage "27") (damage_armor "0")
+/// (health "73") (armor "100") (hitgroup "chest")`
+pub fn cs_attack(i: &str) -> IResult<&str, MessageType> {
+    let (i, attacker) = user(i)?;
+    let (i, attacker_position) = opt(bracket_position)(i)?;
+    let (i, _) = tag(" attacked ")(i)?;
+    let (i, victim) = user(i)?;
+    let (i, victim_position) = opt(bracket_position)(i)?;
+    let (i, _) = tag(" with ")(i)?;
+    let (i, weapon) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, properties) = properties(i)?;
+
+    let number = |key| properties.get(key)?.parse().ok();
+    let (Some(damage), Some(damage_armor), Some(health), Some(armor), Some(hitgroup)) = (
+        number("damage"),
+        number("damage_armor"),
+        number("health"),
+        number("armor"),
+        properties.get("hitgroup"),
+    ) else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::CsAttack {
+            attacker,
+            attacker_position,
+            victim,
+            victim_position,
+            weapon: weapon.to_owned(),
+            damage,
+            damage_armor,
+            health,
+            armor,
+            hitgroup: hitgroup.to_owned(),
+        },
+    ))
+}
+
+pub fn suicide_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" committed suicide with ")(i)?;
+    let (i, weapon) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    Ok((
+        i,
+        MessageType::Suicide {
+            user,
+            weapon: weapon.to_owned(),
+        },
+    ))
+}
+
+pub fn domination_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, from) = user(i)?;
+    let (i, _) = tag_no_case(" triggered ")(i)?;
+    let (i, action) = (tag("\"domination\""), tag("\"revenge\"")).choice(i)?;
+    let (i, _) = tag_no_case(" against ")(i)?;
+    let (i, against) = user(i)?;
+    let (i, properties) = properties(i)?;
+
+    let from_position = properties.get_position("attacker_position");
+    let against_position = properties.get_position("victim_position");
+    let weapon = properties.get("weapon").map(str::to_owned);
+    Ok((
+        i,
+        if action == "\"domination\"" {
+            MessageType::Domination {
+                from,
+                against,
+                from_position,
+                against_position,
+                weapon,
+            }
+        } else {
+            MessageType::Revenge {
+                from,
+                against,
+                from_position,
+                against_position,
+                weapon,
+            }
+        },
+    ))
+}
+
+pub fn damage_event(i: &str) -> IResult<&str, MessageType> {
+    let (i, attacker) = user(i)?;
+    let (i, _) = tag(" triggered \"damage\" against ")(i)?;
+    let (i, victim) = user(i)?;
+    let (i, properties) = properties(i)?;
+    let Some(Ok(amount)) = properties.get("damage").map(str::parse) else {
+        return fail(i);
+    };
+    Ok((
+        i,
+        MessageType::Damage {
+            attacker,
+            victim,
+            amount,
+            weapon: properties.get("weapon").map(str::to_owned),
+            crit: properties.get("crit").map(str::to_owned),
+        },
+    ))
+}
+
+pub fn player_triggered(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag_no_case(" triggered ")(i)?;
+    let (i, action) = delimited(char('"'), take_until1("\""), char('"'))(i)?;
+    let (i, properties) = properties(i)?;
+    Ok((
+        i,
+        MessageType::PlayerTriggered {
+            user,
+            action: action.to_owned(),
+            properties,
+        },
+    ))
+}
+
+/// `x y z` integer world coordinates
+pub fn position(i: &str) -> IResult<&str, Position> {
+    let (i, (x, _, y, _, z)) =
+        (signed_int, char(' '), signed_int, char(' '), signed_int).parse(i)?;
+    Ok((i, Position { x, y, z }))
+}
+
+fn signed_int(i: &str) -> IResult<&str, i32> {
+    let (rest, n) = recognize(pair(opt(char('-')), digit1)).parse(i)?;
+    match n.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => fail(i),
+    }
+}
+
+/// CS-style ` [x y z]` position inline after a user, unlike TF2's `(position "x y z")`
+/// properties
+pub fn bracket_position(i: &str) -> IResult<&str, Position> {
+    preceded(char(' '), delimited(char('['), position, char(']')))(i)
+}
+
+/// Parses the user in a property, which is logged without its surrounding quotes
+fn property_user(properties: &Properties, key: &str) -> Option<User> {
+    let value = properties.get(key)?;
+    user(&format!("\"{value}\"")).ok().map(|(_, user)| user)
+}
+
+pub fn ipv4_with_port(i: &str) -> IResult<&str, (Ipv4Addr, u16)> {
+    let (i, ip) = ipv4(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, port) = port(i)?;
+    Ok((i, (ip, port)))
+}
+
+pub fn port(i: &str) -> IResult<&str, u16> {
+    let (i, port) = digit1(i)?;
+    if let Ok(port) = port.parse::<u16>() {
+        Ok((i, port))
+    } else {
+        fail(i)
+    }
+}
+
+pub fn ipv4(i: &str) -> IResult<&str, Ipv4Addr> {
+    let (i, (a, _, b, _, c, _, d)) = (
+        digit1,
+        char('.'),
+        digit1,
+        char('.'),
+        digit1,
+        char('.'),
+        digit1,
+    )
+        .parse(i)?;
+
+    match (a.parse(), b.parse(), c.parse(), d.parse()) {
+        (Ok(a), Ok(b), Ok(c), Ok(d)) => Ok((i, Ipv4Addr::new(a, b, c, d))),
+        _ => fail(i),
+    }
+}
+
+/// `"name<uid><steamid><team>"`
+pub fn user(i: &str) -> IResult<&str, User> {
+    let (tag, _) = char('"')(i)?;
+    tagged_user(tag, true)
+}
+
+/// `name<uid><steamid><team>`, as some SourceMod chat hooks log users without quotes
+pub fn unquoted_user(i: &str) -> IResult<&str, User> {
+    if i.starts_with('"') {
+        return fail(i);
+    }
+    tagged_user(i, false)
+}
+
+fn tagged_user(tag: &str, quoted: bool) -> IResult<&str, User> {
+    // names may themselves contain `<`, so try each one as the start of the uid tag
+    for (idx, _) in tag.match_indices('<') {
+        let Ok((rest, (uid, steamid, team))) = user_tags(&tag[idx..]) else {
+            continue;
+        };
+        let rest = match (quoted, rest.strip_prefix('"')) {
+            (false, _) => rest,
+            (true, Some(rest)) => rest,
+            (true, None) => continue,
+        };
+        return Ok((
+            rest,
+            User {
+                name: tag[..idx].to_owned(),
+                uid,
+                steamid: steamid.to_owned(),
+                team: team.to_owned(),
+            },
+        ));
+    }
+    fail(tag)
+}
+
+/// Steamid given to placeholder users by [`patch_user_tags`]
+pub const PLACEHOLDER_STEAMID: &str = "[U:0:0]";
+
+/// Appends placeholder `<0><[U:0:0]><>` tags to every quoted value that looks like a user tag
+/// (ends in `>`) but doesn't parse as one, so the raw value becomes the user's name
+pub fn patch_user_tags(i: &str) -> String {
+    i.split('"')
+        .enumerate()
+        .map(|(n, part)| {
+            let quoted = n % 2 == 1;
+            if quoted && part.ends_with('>') && user(&format!("\"{part}\"")).is_err() {
+                format!("{part}<0><{PLACEHOLDER_STEAMID}><>")
+            } else {
+                part.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\"")
+}
+
+/// `<uid><steamid><team>`
+fn user_tags(i: &str) -> IResult<&str, (u32, &str, &str)> {
+    let (i, uid) = delimited(char('<'), digit1, char('>'))(i)?;
+    let (i, steamid) = delimited(char('<'), steamid3.or(tag("BOT")), char('>'))(i)?;
+    // teams are usually words, but Insurgency uses tokens like `#Team_One`
+    let (i, team) = delimited(char('<'), take_while(|c| c != '>' && c != '"'), char('>'))(i)?;
+    let Ok(uid) = uid.parse() else {
+        return fail(i);
+    };
+    Ok((i, (uid, steamid, team)))
+}
+
+/// `[U:1:123456789]`
+fn steamid3(i: &str) -> IResult<&str, &str> {
+    recognize(tuple((
+        tag("[U:"),
+        satisfy(|c| c.is_ascii_digit()),
+        char(':'),
+        digit1,
+        char(']'),
+    )))(i)
+}
+
+pub fn disconnect_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" disconnected (reason ")(i)?;
+    let (i, reason) = delimited(char('"'), take_until1("\""), tag("\")")).parse(i)?;
+    Ok((
+        i,
+        MessageType::Disconnected {
+            user,
+            reason: reason.to_owned(),
+        },
+    ))
+}
+
+pub fn connect_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user(i)?;
+    let (i, _) = tag(" connected, address ")(i)?;
+    // bots have no address, logged as `none` or empty
+    let no_address = opt(tag("none")).map(|_| (Ipv4Addr::UNSPECIFIED, 0));
+    let (i, (ip, port)) =
+        delimited(char('"'), ipv4_with_port.or(no_address), char('"')).parse(i)?;
+    let (i, properties) = properties(i)?;
+    Ok((
+        i,
+        MessageType::Connected {
+            user,
+            ip,
+            port,
+            properties,
+        },
+    ))
+}
+
+pub fn chat_message(i: &str) -> IResult<&str, MessageType> {
+    let (i, user) = user.or(unquoted_user).parse(i)?;
+    let (i, say) = (tag(" say "), tag(" say_team ")).choice(i)?;
+    let (i, _) = char('"')(i)?;
+    // chat is the last field on the line, so its closing quote is the last one and any quotes
+    // before it are part of the message
+    let Some(end) = i.rfind('"').filter(|&end| end > 0) else {
+        return fail(i);
+    };
+    let (message, i) = (&i[..end], &i[(end + 1)..]);
+
+    Ok((
+        i,
+        MessageType::ChatMessage {
+            from: user,
+            message: message.to_owned(),
+            team: say == " say_team ",
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_plausible_for, CritKind, Game, MessageKind, ObjectMovement};
+    use nom::Err;
+    use regex::Regex;
+    use std::collections::HashSet;
+    use std::time::Instant;
+
+    /// The original regex-based [`user`], kept to check the nom version against
+    fn user_regex(i: &str) -> IResult<&str, User> {
+        let re = Regex::new(r#""(.*?)<(\d+)><(\[U:\d:\d+\])><(\w*)?>""#).unwrap();
+        let Some(caps) = re.captures(i) else {
+            return Err(Err::Error(nom::error::Error::new(
+                i,
+                nom::error::ErrorKind::Tag,
+            )));
+        };
+
+        let end = caps.get(0).unwrap().end();
+        let name = caps.get(1).unwrap().as_str();
+        let uid = caps.get(2).unwrap().as_str();
+        let steamid = caps.get(3).unwrap().as_str();
+        let team = caps.get(4).unwrap().as_str();
+
+        let Ok(uid) = uid.parse() else {
+            return fail(i);
+        };
+
+        Ok((
+            &i[end..],
+            User {
+                name: name.to_owned(),
+                uid,
+                steamid: steamid.to_owned(),
+                team: team.to_owned(),
+            },
+        ))
+    }
+
+    const USER_LINES: [&str; 5] = [
+        "\"User<1><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"",
+        "\"TheirUsername<6><[U:1:1324124512]><Red>\" say \"hello\"",
+        "\"Medic<1><[U:1:1]><Blue>\" triggered \"first_heal_after_spawn\" (time \"4.5\")",
+        "\"<Nya> User<2><[U:1:2]><Red>\" joined team \"Red\"",
+        "\"a<b>c<3><[U:1:3]><Spectator>\" disconnected (reason \"Disconnect by user.\")",
+    ];
+
+    #[test]
+    fn user_matches_regex() {
+        for line in USER_LINES {
+            assert!(user(line) == user_regex(line), "{line}");
+        }
+        let (_, angle) = user(USER_LINES[3]).unwrap();
+        assert!(angle.name == "<Nya> User");
+        assert!(angle.uid == 2);
+        assert!(user("\"Name<1><[U:1:1]>\" say \"hi\"").is_err());
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture bench_user`
+    #[test]
+    #[ignore]
+    fn bench_user() {
+        const ITERATIONS: u32 = 10_000;
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            for line in USER_LINES {
+                std::hint::black_box(user(line).unwrap());
+            }
+        }
+        let nom = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            for line in USER_LINES {
+                std::hint::black_box(user_regex(line).unwrap());
+            }
+        }
+        let regex = start.elapsed();
+
+        println!("nom: {nom:?}, regex: {regex:?} ({ITERATIONS} iterations)");
+    }
+
+    #[test]
+    fn connect_message() {
+        const LINE: &str =
+            "\"User<1><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"";
+        let parsed = get_message_type(LINE).unwrap();
+        dbg!(&parsed);
+        match parsed.1 {
+            MessageType::Connected { .. } => (),
+            _ => panic!("grr"),
+        }
+    }
+
+    #[test]
     fn test_ipv4() {
         const IP: &str = "192.168.0.225";
         let ip: Ipv4Addr = ipv4(IP).unwrap().1;
         dbg!(&ip);
         assert!(ip.to_string() == "192.168.0.225");
+        assert!(ipv4("192.168.0.256").is_err());
+    }
+
+    #[test]
+    fn test_ipv4_with_port() {
+        const IP: &str = "192.168.0.115:12345";
+        let (ip, port) = ipv4_with_port(IP).unwrap().1;
+        dbg!(&ip);
+        assert!(ip.to_string() == "192.168.0.115");
+        assert!(port == 12345);
+    }
+
+    #[test]
+    fn start_map() {
+        const LINE: &str =
+            "Started map \"koth_highpass\" (CRC \"505b4fbf2a1661d2fb1b96f444ef268c\")";
+        let parsed = get_message_type(LINE).unwrap();
+        assert!(
+            parsed.1
+                == MessageType::StartedMap {
+                    name: "koth_highpass".to_owned(),
+                    crc: Some("505b4fbf2a1661d2fb1b96f444ef268c".to_owned())
+                }
+        );
+    }
+
+    #[test]
+    fn start_map_without_crc() {
+        const LINE: &str = "Started map \"cp_dustbowl\"";
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::StartedMap {
+                    name: "cp_dustbowl".to_owned(),
+                    crc: None,
+                }
+        );
+    }
+
+    #[test]
+    fn round_win() {
+        const LINE: &str = "World triggered \"Round_Win\" (winner \"Red\")";
+        let parsed = get_message_type(LINE).unwrap().1;
+        assert!(
+            parsed
+                == MessageType::WorldTriggered {
+                    action: "Round_Win".to_owned(),
+                    properties: Properties(vec![("winner".to_owned(), "Red".to_owned())]),
+                }
+        );
+        assert!(parsed.round_winner() == Some("Red"));
+
+        const START: &str = "World triggered \"Round_Start\"";
+        let parsed = get_message_type(START).unwrap().1;
+        assert!(parsed.round_winner().is_none());
+    }
+
+    #[test]
+    fn first_heal_after_spawn() {
+        const LINE: &str =
+            "\"Medic<1><[U:1:1]><Red>\" triggered \"first_heal_after_spawn\" (time \"4.5\")";
+        let parsed = get_message_type(LINE).unwrap().1;
+        match &parsed {
+            MessageType::PlayerTriggered { user, action, .. } => {
+                assert!(user.name == "Medic");
+                assert!(action == "first_heal_after_spawn");
+            }
+            _ => panic!("not a player trigger"),
+        }
+        assert!(parsed.first_heal_time() == Some(4.5));
+    }
+
+    #[test]
+    fn koth_point_captured() {
+        const LINE: &str = "World triggered \"koth_point_captured\" (cp \"0\") (capteam \"2\")";
+        let parsed = get_message_type(LINE).unwrap().1;
+        assert!(parsed.koth_capture() == Some((0, 2)));
+        assert!(parsed.round_winner().is_none());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_round_trip() {
+        const LINES: [&str; 4] = [
+            "\"User<1><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"",
+            "Started map \"koth_highpass\" (CRC \"505b4fbf2a1661d2fb1b96f444ef268c\")",
+            "World triggered \"Round_Win\" (winner \"Red\")",
+            "not a known message",
+        ];
+        for line in LINES {
+            let parsed = MessageType::from_message(line);
+            let bytes = parsed.to_bytes().unwrap();
+            assert!(MessageType::from_bytes(&bytes).unwrap() == parsed);
+        }
+    }
+
+    #[test]
+    fn team_scores() {
+        const CURRENT: &str = "Team \"Blue\" current score \"2\" with \"6\" players";
+        assert!(
+            get_message_type(CURRENT).unwrap().1
+                == MessageType::TeamCurrentScore {
+                    team: "Blue".to_owned(),
+                    score: 2,
+                    players: 6,
+                }
+        );
+
+        const FINAL: &str = "Team \"Red\" final score \"3\" with \"6\" players";
+        assert!(
+            get_message_type(FINAL).unwrap().1
+                == MessageType::TeamFinalScore {
+                    team: "Red".to_owned(),
+                    score: 3,
+                    players: 6,
+                }
+        );
+    }
+
+    #[test]
+    fn domination_with_positions() {
+        const LINE: &str = "\"A<2><[U:1:2]><Red>\" triggered \"domination\" against \"B<3><[U:1:3]><Blue>\" (attacker_position \"-1234 567 89\") (victim_position \"12 -34 56\")";
+        match get_message_type(LINE).unwrap().1 {
+            MessageType::Domination {
+                from,
+                against,
+                from_position,
+                against_position,
+                weapon,
+            } => {
+                assert!(from.name == "A");
+                assert!(weapon.is_none());
+                assert!(against.name == "B");
+                assert!(
+                    from_position
+                        == Some(Position {
+                            x: -1234,
+                            y: 567,
+                            z: 89
+                        })
+                );
+                assert!(
+                    against_position
+                        == Some(Position {
+                            x: 12,
+                            y: -34,
+                            z: 56
+                        })
+                );
+            }
+            _ => panic!("not a domination"),
+        }
+
+        const REVENGE: &str =
+            "\"B<3><[U:1:3]><Blue>\" triggered \"revenge\" against \"A<2><[U:1:2]><Red>\"";
+        match get_message_type(REVENGE).unwrap().1 {
+            MessageType::Revenge {
+                from_position,
+                against_position,
+                ..
+            } => {
+                assert!(from_position.is_none());
+                assert!(against_position.is_none());
+            }
+            _ => panic!("not a revenge"),
+        }
+    }
+
+    #[test]
+    fn kill_remainder() {
+        const LINE: &str = "\"A<1><[U:1:1]><Red>\" killed \"V<2><[U:1:2]><Blue>\" with \"scattergun\" (attacker_position \"1 2 3\") (victim_position \"4 5 6\")";
+        let (parsed, rest) = MessageType::parse_with_remainder(LINE);
+        match parsed {
+            MessageType::Killed {
+                attacker,
+                victim,
+                weapon,
+                assister,
+            } => {
+                assert!(attacker.name == "A");
+                assert!(victim.name == "V");
+                assert!(weapon == "scattergun");
+                assert!(assister.is_none());
+            }
+            _ => panic!("not a kill"),
+        }
+        assert!(rest == " (attacker_position \"1 2 3\") (victim_position \"4 5 6\")");
+
+        const UNKNOWN: &str = "something else entirely";
+        assert!(MessageType::parse_with_remainder(UNKNOWN) == (MessageType::Unknown, UNKNOWN));
+    }
+
+    #[test]
+    fn startup_network() {
+        const PUBLIC: &str = "Public IP is 1.2.3.4.";
+        assert!(
+            get_message_type(PUBLIC).unwrap().1
+                == MessageType::PublicIp {
+                    ip: "1.2.3.4".parse().unwrap()
+                }
+        );
+
+        const NETWORK: &str =
+            "Network: IP 0.0.0.0, mode MP, dedicated Yes, ports 27015 SV / 27005 CL";
+        assert!(
+            get_message_type(NETWORK).unwrap().1
+                == MessageType::NetworkConfig {
+                    ip: "0.0.0.0".parse().unwrap(),
+                    sv_port: 27015,
+                    cl_port: 27005,
+                }
+        );
+    }
+
+    #[test]
+    fn exec_configs() {
+        const SUCCESS: &str = "Executing dedicated server config file server.cfg";
+        assert!(
+            get_message_type(SUCCESS).unwrap().1
+                == MessageType::ExecConfig {
+                    file: "server.cfg".to_owned(),
+                    success: true,
+                }
+        );
+
+        const MISSING: &str = "exec: couldn't exec missing.cfg";
+        assert!(
+            get_message_type(MISSING).unwrap().1
+                == MessageType::ExecConfig {
+                    file: "missing.cfg".to_owned(),
+                    success: false,
+                }
+        );
+    }
+
+    #[test]
+    fn kill_with_assister() {
+        const LINE: &str = "\"A<1><[U:1:1]><Red>\" killed \"V<2><[U:1:2]><Blue>\" with \"scattergun\" (assist \"Assister<3><[U:1:3]><Red>\") (attacker_position \"1 2 3\")";
+        let (parsed, rest) = MessageType::parse_with_remainder(LINE);
+        match parsed {
+            MessageType::Killed { assister, .. } => {
+                let assister = assister.unwrap();
+                assert!(assister.name == "Assister");
+                assert!(assister.uid == 3);
+                assert!(assister.team == "Red");
+            }
+            _ => panic!("not a kill"),
+        }
+        assert!(rest == " (attacker_position \"1 2 3\")");
+    }
+
+    #[test]
+    fn eq_by_identity() {
+        const A: &str = "\"User<1><[U:1:123456789]><Red>\" say \"hello\"";
+        const B: &str = "\"uSER<1><[U:1:123456789]><Red>\" say \"hello\"";
+        const OTHER: &str = "\"User<2><[U:1:987654321]><Red>\" say \"hello\"";
+        let (a, b, other) = (
+            MessageType::from_message(A),
+            MessageType::from_message(B),
+            MessageType::from_message(OTHER),
+        );
+        assert!(a != b);
+        assert!(a.eq_by_identity(&b));
+        assert!(!a.eq_by_identity(&other));
+    }
+
+    #[test]
+    fn exotic_player_triggers() {
+        let lines = [
+            (
+                "\"Hale<1><[U:1:1]><Blue>\" triggered \"player_escaped\" (position \"1 2 3\")",
+                "player_escaped",
+                vec![("position", "1 2 3")],
+            ),
+            (
+                "\"Scout<2><[U:1:2]><Red>\" triggered \"player_death\" (class \"scout\") (team \"Red\")",
+                "player_death",
+                vec![("class", "scout"), ("team", "Red")],
+            ),
+            (
+                "\"Heavy<3><[U:1:3]><Red>\" triggered \"mvm_money_pickup\"",
+                "mvm_money_pickup",
+                vec![],
+            ),
+        ];
+        for (line, expected_action, expected_properties) in lines {
+            match get_message_type(line).unwrap().1 {
+                MessageType::PlayerTriggered {
+                    action, properties, ..
+                } => {
+                    assert!(action == expected_action);
+                    assert!(properties.len() == expected_properties.len());
+                    assert!(properties
+                        .iter()
+                        .zip(expected_properties)
+                        .all(|((k, v), (ek, ev))| k == ek && v == ev));
+                }
+                _ => panic!("not a player trigger: {line}"),
+            }
+        }
+    }
+
+    #[test]
+    fn mvm_waves() {
+        const COMPLETE: &str =
+            "World triggered \"Mann Vs Machine Event\" (event \"wave_complete\") (wave \"3\")";
+        assert!(
+            get_message_type(COMPLETE).unwrap().1
+                == MessageType::MvmWave {
+                    event: MvmWaveEvent::WaveComplete,
+                    wave: 3,
+                }
+        );
+
+        const MISSION: &str =
+            "World triggered \"Mann Vs Machine Event\" (event \"mission_complete\") (wave \"7\")";
+        assert!(
+            get_message_type(MISSION).unwrap().1
+                == MessageType::MvmWave {
+                    event: MvmWaveEvent::MissionComplete,
+                    wave: 7,
+                }
+        );
+    }
+
+    #[test]
+    fn carry_and_drop_object() {
+        const CARRY: &str = "\"Eng<1><[U:1:1]><Blue>\" triggered \"player_carryobject\" (object \"OBJ_SENTRYGUN\") (position \"1 2 3\")";
+        let parsed = get_message_type(CARRY).unwrap().1;
+        assert!(parsed.object_movement() == Some(ObjectMovement::CarryObject));
+        assert!(parsed.object() == Some("OBJ_SENTRYGUN"));
+
+        const DROP: &str = "\"Eng<1><[U:1:1]><Blue>\" triggered \"player_dropobject\" (object \"OBJ_DISPENSER\") (position \"4 5 6\")";
+        let parsed = get_message_type(DROP).unwrap().1;
+        assert!(parsed.object_movement() == Some(ObjectMovement::DropObject));
+        assert!(parsed.object() == Some("OBJ_DISPENSER"));
+    }
+
+    #[test]
+    fn kill_deltas() {
+        const KILL: &str =
+            "\"A<1><[U:1:1]><Red>\" killed \"V<2><[U:1:2]><Blue>\" with \"scattergun\"";
+        let (user, delta) = get_message_type(KILL).unwrap().1.kill_delta().unwrap();
+        assert!(user.name == "A");
+        assert!(delta == 1);
+
+        const TEAM_KILL: &str =
+            "\"A<1><[U:1:1]><Red>\" killed \"T<3><[U:1:3]><Red>\" with \"scattergun\"";
+        let (user, delta) = get_message_type(TEAM_KILL).unwrap().1.kill_delta().unwrap();
+        assert!(user.name == "A");
+        assert!(delta == -1);
+
+        const SUICIDE: &str = "\"A<1><[U:1:1]><Red>\" committed suicide with \"world\"";
+        let (user, delta) = get_message_type(SUICIDE).unwrap().1.kill_delta().unwrap();
+        assert!(user.name == "A");
+        assert!(delta == -1);
+
+        const CHAT: &str = "\"A<1><[U:1:1]><Red>\" say \"gg\"";
+        assert!(get_message_type(CHAT).unwrap().1.kill_delta().is_none());
+    }
+
+    #[test]
+    fn player_index_assignment() {
+        const LINE: &str = "Assigning unique id 5 to \"TheirUsername\"";
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::PlayerIndexAssigned {
+                    name: "TheirUsername".to_owned(),
+                    index: 5,
+                }
+        );
+    }
+
+    #[test]
+    fn admin_server_message() {
+        const LINE: &str = "Server message: \"map changes in 5 minutes\"";
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::ServerMessage {
+                    text: "map changes in 5 minutes".to_owned()
+                }
+        );
+    }
+
+    #[test]
+    fn disabled_parser_groups() {
+        const KILL: &str =
+            "\"A<1><[U:1:1]><Red>\" killed \"V<2><[U:1:2]><Blue>\" with \"scattergun\"";
+        const CHAT: &str = "\"A<1><[U:1:1]><Red>\" say \"gg\"";
+        let config = ParserConfig {
+            kills: false,
+            ..Default::default()
+        };
+        assert!(MessageType::from_message_with_config(KILL, &config).is_unknown());
+        assert!(!MessageType::from_message(KILL).is_unknown());
+
+        let chat_only = ParserConfig {
+            chat: true,
+            ..ParserConfig::none()
+        };
+        assert!(MessageType::from_message_with_config(KILL, &chat_only).is_unknown());
+        match MessageType::from_message_with_config(CHAT, &chat_only) {
+            MessageType::ChatMessage { message, .. } => assert!(message == "gg"),
+            _ => panic!("chat should still parse"),
+        }
+    }
+
+    #[test]
+    fn ban_list_changes() {
+        const ADDIP: &str = "addip 0 \"192.168.0.1\"";
+        assert!(
+            get_message_type(ADDIP).unwrap().1
+                == MessageType::BanListChange {
+                    action: "addip".to_owned(),
+                    target: "192.168.0.1".to_owned(),
+                }
+        );
+
+        const REMOVEID: &str = "removeid [U:1:1324124512]";
+        assert!(
+            get_message_type(REMOVEID).unwrap().1
+                == MessageType::BanListChange {
+                    action: "removeid".to_owned(),
+                    target: "[U:1:1324124512]".to_owned(),
+                }
+        );
+    }
+
+    #[test]
+    fn point_captured_forms() {
+        const TEAM: &str = "Team \"Blue\" triggered \"pointcaptured\" (cp \"1\") (cpname \"#Gravelpit_cap_B\") (numcappers \"1\") (player1 \"Player<1><[U:1:1]><Blue>\") (position1 \"1 2 3\")";
+        let parsed = get_message_type(TEAM).unwrap().1;
+        assert!(matches!(parsed, MessageType::TeamTriggered { ref team, .. } if team == "Blue"));
+        assert!(parsed.capture() == Some(("1", "#Gravelpit_cap_B")));
+
+        const PLAYER: &str = "\"Player<1><[U:1:1]><Blue>\" triggered \"pointcaptured\" (cp \"1\") (cpname \"#Gravelpit_cap_B\")";
+        let parsed = get_message_type(PLAYER).unwrap().1;
+        assert!(matches!(parsed, MessageType::PlayerTriggered { .. }));
+        assert!(parsed.capture() == Some(("1", "#Gravelpit_cap_B")));
+    }
+
+    #[test]
+    fn log_file_game() {
+        const TF: &str =
+            "Log file started (file \"logs/L0209000.log\") (game \"/home/tf2/tf\") (version \"8622567\")";
+        const TF_DIR: &str =
+            "Log file started (file \"logs/L0209000.log\") (game \"tf\") (version \"8622567\")";
+        const CSTRIKE: &str =
+            "Log file started (file \"logs/L0209000.log\") (game \"cstrike\") (version \"1\")";
+        assert!(get_message_type(TF).unwrap().1.game() == Some(Game::Tf2));
+        assert!(get_message_type(TF_DIR).unwrap().1.game() == Some(Game::Tf2));
+        assert!(get_message_type(CSTRIKE).unwrap().1.game() == Some(Game::CounterStrike));
+        assert!(MessageType::LogFileClosed.game().is_none());
+        assert!(Game::from("C:\\srcds\\garrysmod") == Game::Other("garrysmod".to_owned()));
+    }
+
+    #[test]
+    fn objective_players_with_positions() {
+        const LINE: &str = "Team \"Blue\" triggered \"pointcaptured\" (cp \"1\") (cpname \"#Gravelpit_cap_B\") (numcappers \"2\") (player1 \"A<1><[U:1:1]><Blue>\") (position1 \"1 2 3\") (player2 \"B<2><[U:1:2]><Blue>\")";
+        let players = get_message_type(LINE).unwrap().1.objective_players();
+        assert!(players.len() == 2);
+        assert!(players[0].0.name == "A");
+        assert!(players[0].1 == Some(Position { x: 1, y: 2, z: 3 }));
+        assert!(players[1].0.name == "B");
+        assert!(players[1].1.is_none());
+
+        const SUFFIXED: &str = "Team \"Red\" triggered \"captureblocked\" (cp \"0\") (player1 \"A<1><[U:1:1]><Red>\") (player1_position \"-4 5 -6\")";
+        let players = get_message_type(SUFFIXED).unwrap().1.objective_players();
+        assert!(players[0].1 == Some(Position { x: -4, y: 5, z: -6 }));
+    }
+
+    #[test]
+    fn lenient_corrupt_steamid() {
+        const LINE: &str = "\"User<1><[U:1:12ab]><Red>\" say \"hello\"";
+        assert!(MessageType::from_message(LINE).is_unknown());
+        match MessageType::from_message_lenient(LINE) {
+            MessageType::ChatMessage { from, message, .. } => {
+                assert!(from.name == "User<1><[U:1:12ab]><Red>");
+                assert!(from.uid == 0);
+                assert!(from.steamid.is_empty());
+                assert!(message == "hello");
+            }
+            other => panic!("expected lenient chat, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn game_coordinator_status() {
+        const CONNECT: &str = "Connection to game coordinator established.";
+        assert!(
+            get_message_type(CONNECT).unwrap().1
+                == MessageType::BackendStatus {
+                    event: BackendEvent::GcConnected
+                }
+        );
+
+        const DISCONNECT: &str = "Lost connection to game coordinator";
+        assert!(
+            get_message_type(DISCONNECT).unwrap().1
+                == MessageType::BackendStatus {
+                    event: BackendEvent::GcDisconnected
+                }
+        );
+
+        assert!(MessageType::from_message("Connection to game coordinator pending").is_unknown());
+    }
+
+    #[test]
+    fn hash_users_and_messages() {
+        const LINES: [&str; 3] = [
+            "\"A<1><[U:1:1]><Red>\" say \"hi\"",
+            "\"A<1><[U:1:1]><Red>\" say \"gg\"",
+            "\"B<2><[U:1:2]><Red>\" say \"hi\"",
+        ];
+        let mut users = HashSet::new();
+        let mut messages = HashSet::new();
+        for line in LINES.iter().chain(&LINES) {
+            let parsed = MessageType::from_message(*line);
+            if let MessageType::ChatMessage { from, .. } = &parsed {
+                users.insert(from.clone());
+            }
+            messages.insert(parsed);
+        }
+        assert!(users.len() == 2);
+        assert!(messages.len() == 3);
+    }
+
+    #[test]
+    fn rcon_sources() {
+        const IP: &str = r#"rcon from "1.2.3.4:27015": command "status""#;
+        assert!(
+            get_message_type(IP).unwrap().1
+                == MessageType::Rcon {
+                    source: RconSource::Ip("1.2.3.4".parse().unwrap(), 27015),
+                    command: "status".to_owned(),
+                }
+        );
+
+        const HOST: &str = r#"rcon from "admin.example.com:27015": command "status""#;
+        assert!(
+            get_message_type(HOST).unwrap().1
+                == MessageType::Rcon {
+                    source: RconSource::Host("admin.example.com".to_owned(), 27015),
+                    command: "status".to_owned(),
+                }
+        );
     }
 
     #[test]
-    fn test_ipv4_with_port() {
-        const IP: &str = "192.168.0.115:12345";
-        let (ip, port) = ipv4_with_port(IP).unwrap().1;
-        dbg!(&ip);
-        assert!(ip.to_string() == "192.168.0.115");
-        assert!(port == 12345);
+    fn server_ip_from_either_phrasing() {
+        let lines = [
+            "Public IP is 1.2.3.4.",
+            "Network: IP 1.2.3.4, mode MP, dedicated Yes, ports 27015 SV / 27005 CL",
+        ];
+        for line in lines {
+            let msg = get_message_type(line).unwrap().1;
+            assert!(msg.server_ip() == Some("1.2.3.4".parse().unwrap()));
+        }
+        assert!(get_message_type("Log file closed")
+            .unwrap()
+            .1
+            .server_ip()
+            .is_none());
     }
 
     #[test]
-    fn start_map() {
+    fn split_clantags() {
+        let split = |name: &str| {
+            User {
+                name: name.to_owned(),
+                uid: 2,
+                steamid: "[U:1:1]".to_owned(),
+                team: "Red".to_owned(),
+            }
+            .split_clantag()
+        };
+        assert!(split("[CLAN] Nick") == (Some("CLAN".to_owned()), "Nick".to_owned()));
+        assert!(split("(ab)cd") == (Some("ab".to_owned()), "cd".to_owned()));
+        assert!(split("CLAN | Nick") == (Some("CLAN".to_owned()), "Nick".to_owned()));
+        assert!(split("TAG::Nick|x") == (Some("TAG".to_owned()), "Nick|x".to_owned()));
+        assert!(split("Nick") == (None, "Nick".to_owned()));
+        assert!(split("[] Nick") == (None, "[] Nick".to_owned()));
+        assert!(split("Nick |") == (None, "Nick |".to_owned()));
+    }
+
+    #[test]
+    fn match_status() {
+        const LINE: &str = r#"MatchStatus: Score: 5:3 on map "de_mirage" RoundsPlayed: 8"#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::MatchStatus {
+                    ct_score: 5,
+                    t_score: 3,
+                    map: "de_mirage".to_owned(),
+                    rounds: 8,
+                }
+        );
+    }
+
+    #[test]
+    fn accolade() {
         const LINE: &str =
-            "Started map \"koth_highpass\" (CRC \"505b4fbf2a1661d2fb1b96f444ef268c\")";
-        let parsed = get_message_type(LINE).unwrap();
+            "ACCOLADE, FINAL: {3k},\tSome <Player><12>,\tVALUE: 2.000000,\tPOS: 1,\tSCORE: 40.000000";
         assert!(
-            parsed.1
-                == MessageType::StartedMap {
-                    name: "koth_highpass".to_owned(),
-                    crc: "505b4fbf2a1661d2fb1b96f444ef268c".to_owned()
+            get_message_type(LINE).unwrap().1
+                == MessageType::Accolade(Accolade {
+                    category: "3k".to_owned(),
+                    user: User {
+                        name: "Some <Player>".to_owned(),
+                        uid: 12,
+                        steamid: String::new(),
+                        team: String::new(),
+                    },
+                    value: 2.0,
+                    position: 1,
+                    score: 40.0,
+                })
+        );
+    }
+
+    #[test]
+    fn object_detonated() {
+        const LINE: &str = r#""Engie<5><[U:1:55]><Blue>" triggered "object_detonated" (object "OBJ_SENTRYGUN") (position "10 -20 30")"#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::ObjectDetonated {
+                    user: User {
+                        name: "Engie".to_owned(),
+                        uid: 5,
+                        steamid: "[U:1:55]".to_owned(),
+                        team: "Blue".to_owned(),
+                    },
+                    object: BuildingType::Sentry,
+                }
+        );
+    }
+
+    #[test]
+    fn affected_team() {
+        let lines = [
+            (
+                r#""Alice<2><[U:1:1]><Unassigned>" joined team "Red""#,
+                Some("Red"),
+            ),
+            (r#""Alice<2><[U:1:1]><Red>" say "gg""#, Some("Red")),
+            (
+                r#"World triggered "Round_Win" (winner "Blue")"#,
+                Some("Blue"),
+            ),
+            (r#"World triggered "Round_Start""#, None),
+            (
+                r#""Alice<2><[U:1:1]><Red>" killed "Bob<3><[U:1:2]><Blue>" with "scattergun""#,
+                Some("Red"),
+            ),
+            ("Log file closed", None),
+        ];
+        for (line, team) in lines {
+            assert!(get_message_type(line).unwrap().1.affected_team() == team);
+        }
+    }
+
+    #[test]
+    fn building_types() {
+        let objects = [
+            ("OBJ_SENTRYGUN", BuildingType::Sentry),
+            ("OBJ_DISPENSER", BuildingType::Dispenser),
+            ("OBJ_TELEPORTER", BuildingType::Teleporter),
+            ("OBJ_TELEPORTER_ENTRANCE", BuildingType::TeleporterEntrance),
+            ("OBJ_TELEPORTER_EXIT", BuildingType::TeleporterExit),
+            ("OBJ_ATTACHMENT_SAPPER", BuildingType::Sapper),
+            (
+                "OBJ_SOMETHING",
+                BuildingType::Other("OBJ_SOMETHING".to_owned()),
+            ),
+        ];
+        for (object, building) in objects {
+            let line = format!(
+                r#""Spy<2><[U:1:1]><Blue>" triggered "killedobject" (object "{object}") (weapon "knife") (objectowner "Engie<5><[U:1:55]><Red>")"#
+            );
+            assert!(get_message_type(&line).unwrap().1.building_type() == Some(building));
+        }
+        const CHAT: &str = r#""Spy<2><[U:1:1]><Blue>" say "hi""#;
+        assert!(get_message_type(CHAT).unwrap().1.building_type().is_none());
+    }
+
+    #[test]
+    fn cs_attacks() {
+        let user = |name: &str, uid, steamid: &str, team: &str| User {
+            name: name.to_owned(),
+            uid,
+            steamid: steamid.to_owned(),
+            team: team.to_owned(),
+        };
+
+        const HEADSHOT: &str = r#""A<1><[U:1:1]><CT>" [-1100 200 -30] attacked "B<2><[U:1:2]><TERRORIST>" [50 -60 70] with "ak47" (damage "111") (damage_armor "0") (health "0") (armor "0") (hitgroup "head")"#;
+        assert!(
+            get_message_type(HEADSHOT).unwrap().1
+                == MessageType::CsAttack {
+                    attacker: user("A", 1, "[U:1:1]", "CT"),
+                    attacker_position: Some(Position {
+                        x: -1100,
+                        y: 200,
+                        z: -30
+                    }),
+                    victim: user("B", 2, "[U:1:2]", "TERRORIST"),
+                    victim_position: Some(Position {
+                        x: 50,
+                        y: -60,
+                        z: 70
+                    }),
+                    weapon: "ak47".to_owned(),
+                    damage: 111,
+                    damage_armor: 0,
+                    health: 0,
+                    armor: 0,
+                    hitgroup: "head".to_owned(),
+                }
+        );
+
+        const BODY: &str = r#""A<1><[U:1:1]><CT>" attacked "B<2><[U:1:2]><TERRORIST>" with "ak47" (damage "27") (damage_armor "3") (health "73") (armor "97") (hitgroup "chest")"#;
+        assert!(
+            get_message_type(BODY).unwrap().1
+                == MessageType::CsAttack {
+                    attacker: user("A", 1, "[U:1:1]", "CT"),
+                    attacker_position: None,
+                    victim: user("B", 2, "[U:1:2]", "TERRORIST"),
+                    victim_position: None,
+                    weapon: "ak47".to_owned(),
+                    damage: 27,
+                    damage_armor: 3,
+                    health: 73,
+                    armor: 97,
+                    hitgroup: "chest".to_owned(),
+                }
+        );
+    }
+
+    #[test]
+    fn money_change() {
+        const PURCHASE: &str = r#""P<1><[U:1:1]><CT>" money change 16000-2700 = $13300 (tracked) (purchase: weapon_ak47)"#;
+        assert!(
+            get_message_type(PURCHASE).unwrap().1
+                == MessageType::MoneyChange {
+                    user: User {
+                        name: "P".to_owned(),
+                        uid: 1,
+                        steamid: "[U:1:1]".to_owned(),
+                        team: "CT".to_owned(),
+                    },
+                    before: 16000,
+                    delta: -2700,
+                    after: 13300,
+                    purchase: Some("weapon_ak47".to_owned()),
+                }
+        );
+
+        const REWARD: &str = r#""P<1><[U:1:1]><CT>" money change 800+3250 = $4050 (tracked)"#;
+        let MessageType::MoneyChange {
+            delta, purchase, ..
+        } = get_message_type(REWARD).unwrap().1
+        else {
+            panic!("not a money change");
+        };
+        assert!(delta == 3250 && purchase.is_none());
+    }
+
+    #[test]
+    fn server_cvars() {
+        let cvar =
+            |var: &str, value: &str, limits: Option<(&str, &str, &str)>| MessageType::ServerCvar {
+                var: var.to_owned(),
+                value: value.to_owned(),
+                default: limits.map(|(d, _, _)| d.to_owned()),
+                min: limits.map(|(_, m, _)| m.to_owned()),
+                max: limits.map(|(_, _, m)| m.to_owned()),
+            };
+
+        const CHANGED: &str = r#"server_cvar: "mp_timelimit" "30""#;
+        assert!(get_message_type(CHANGED).unwrap().1 == cvar("mp_timelimit", "30", None));
+
+        const PLAIN: &str = r#""sv_password" = """#;
+        assert!(get_message_type(PLAIN).unwrap().1 == cvar("sv_password", "", None));
+
+        const EXTENDED: &str =
+            r#""mp_timelimit" = "30" ( def. "0" min. "0.000000" max. "9999.000000" )"#;
+        assert!(
+            get_message_type(EXTENDED).unwrap().1
+                == cvar("mp_timelimit", "30", Some(("0", "0.000000", "9999.000000")))
+        );
+
+        const DEFAULT_ONLY: &str = r#""hostname" = "srv" ( def. "Team Fortress" )"#;
+        let MessageType::ServerCvar {
+            default, min, max, ..
+        } = get_message_type(DEFAULT_ONLY).unwrap().1
+        else {
+            panic!("not a cvar");
+        };
+        assert!(default.as_deref() == Some("Team Fortress") && min.is_none() && max.is_none());
+    }
+
+    #[test]
+    fn bomb_events() {
+        let lines = [
+            (
+                r#""P<1><[U:1:1]><TERRORIST>" triggered "Planted_The_Bomb""#,
+                BombAction::Planted,
+            ),
+            (
+                r#""P<2><[U:1:2]><CT>" triggered "Began_Bomb_Defuse_With_Kit""#,
+                BombAction::BeganDefuse { with_kit: true },
+            ),
+            (
+                r#""P<2><[U:1:2]><CT>" triggered "Defused_The_Bomb""#,
+                BombAction::Defused,
+            ),
+        ];
+        for (line, action) in lines {
+            let MessageType::BombEvent { event, user } = get_message_type(line).unwrap().1 else {
+                panic!("not a bomb event: {line}");
+            };
+            assert!(event == action);
+            assert!(user.uid != 0);
+        }
+    }
+
+    #[test]
+    fn chat_text_plain() {
+        const LINE: &str =
+            "\"P<1><[U:1:1]><Red>\" say \"\x01hello \x03there\x07FF00AAfriend\x08FF00AA80!\x07\"";
+        let msg = get_message_type(LINE).unwrap().1;
+        assert!(msg.chat_text_plain().as_deref() == Some("hello therefriend!"));
+        let MessageType::ChatMessage { message, .. } = &msg else {
+            panic!("not a chat message");
+        };
+        assert!(message.contains("\x07FF00AA"));
+
+        const NOT_CHAT: &str = "Log file closed";
+        assert!(get_message_type(NOT_CHAT)
+            .unwrap()
+            .1
+            .chat_text_plain()
+            .is_none());
+    }
+
+    #[test]
+    fn bracket_positions() {
+        assert!(
+            bracket_position(" [123 456 78]")
+                == Ok((
+                    "",
+                    Position {
+                        x: 123,
+                        y: 456,
+                        z: 78
+                    }
+                ))
+        );
+        assert!(
+            bracket_position(" [-123 -456 -78] killed")
+                == Ok((
+                    " killed",
+                    Position {
+                        x: -123,
+                        y: -456,
+                        z: -78
+                    }
+                ))
+        );
+        assert!(bracket_position(r#" (position "1 2 3")"#).is_err());
+
+        const CS_KILL: &str = r#""A<1><[U:1:1]><CT>" [-1100 200 -30] killed "B<2><[U:1:2]><TERRORIST>" [50 -60 70] with "ak47" (headshot)"#;
+        let MessageType::Killed {
+            attacker,
+            victim,
+            weapon,
+            ..
+        } = get_message_type(CS_KILL).unwrap().1
+        else {
+            panic!("not a kill");
+        };
+        assert!(attacker.name == "A" && victim.name == "B" && weapon == "ak47");
+    }
+
+    #[test]
+    fn flag_defended() {
+        const LINE: &str = r#""P<2><[U:1:2]><Blue>" triggered "flagevent" (event "defended") (position "100 -200 300")"#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::FlagEvent {
+                    user: User {
+                        name: "P".to_owned(),
+                        uid: 2,
+                        steamid: "[U:1:2]".to_owned(),
+                        team: "Blue".to_owned(),
+                    },
+                    event: FlagEventKind::Defended,
+                    position: Some(Position {
+                        x: 100,
+                        y: -200,
+                        z: 300
+                    }),
+                }
+        );
+    }
+
+    #[test]
+    fn cs_round_end() {
+        const SAVED: &str = r#"Team "CT" triggered "SFUI_Notice_Target_Saved" (CT "1") (T "0")"#;
+        assert!(
+            get_message_type(SAVED).unwrap().1
+                == MessageType::TeamTriggered {
+                    team: "CT".to_owned(),
+                    action: "SFUI_Notice_Target_Saved".to_owned(),
+                    properties: Properties(vec![
+                        ("CT".to_owned(), "1".to_owned()),
+                        ("T".to_owned(), "0".to_owned())
+                    ]),
+                }
+        );
+
+        const SCORED: &str = r#"Team "TERRORIST" scored "1" with "5" players"#;
+        assert!(
+            get_message_type(SCORED).unwrap().1
+                == MessageType::TeamCurrentScore {
+                    team: "TERRORIST".to_owned(),
+                    score: 1,
+                    players: 5,
+                }
+        );
+    }
+
+    #[test]
+    fn tv_connects() {
+        const GOTV: &str = r#""GOTV<2><BOT><>" connected, address "none""#;
+        let MessageType::Connected { user, ip, port, .. } = get_message_type(GOTV).unwrap().1
+        else {
+            panic!("not a connect");
+        };
+        assert!(user.is_tv() && user.is_bot());
+        assert!(ip == Ipv4Addr::UNSPECIFIED && port == 0);
+
+        const BOT: &str = r#""Bot Scout<3><BOT><Red>" connected, address """#;
+        let MessageType::Connected { user, .. } = get_message_type(BOT).unwrap().1 else {
+            panic!("not a connect");
+        };
+        assert!(user.is_bot() && !user.is_tv());
+
+        const PLAYER: &str = r#""GOTV<4><[U:1:4]><>" connected, address "1.2.3.4:27005""#;
+        let MessageType::Connected { user, .. } = get_message_type(PLAYER).unwrap().1 else {
+            panic!("not a connect");
+        };
+        assert!(!user.is_tv());
+    }
+
+    #[test]
+    fn host_errors() {
+        const HOST_ERROR: &str = "Host_Error: CL_ParsePacketEntities: entity 12 not found";
+        assert!(
+            get_message_type(HOST_ERROR).unwrap().1
+                == MessageType::FatalError {
+                    text: "CL_ParsePacketEntities: entity 12 not found".to_owned(),
+                }
+        );
+
+        const FATAL: &str = "FATAL ERROR: out of memory";
+        assert!(
+            get_message_type(FATAL).unwrap().1
+                == MessageType::FatalError {
+                    text: "out of memory".to_owned(),
+                }
+        );
+    }
+
+    #[test]
+    fn connect_properties() {
+        const LINE: &str =
+            r#""A<1><[U:1:1]><>" connected, address "1.2.3.4:27005" (connection "direct")"#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::Connected {
+                    user: User {
+                        name: "A".to_owned(),
+                        uid: 1,
+                        steamid: "[U:1:1]".to_owned(),
+                        team: String::new(),
+                    },
+                    ip: Ipv4Addr::new(1, 2, 3, 4),
+                    port: 27005,
+                    properties: Properties(vec![("connection".to_owned(), "direct".to_owned())]),
+                }
+        );
+    }
+
+    #[test]
+    fn killed_objects() {
+        const SPY: &str = r#""Spy<2><[U:1:1]><Blue>" triggered "killedobject" (object "OBJ_SENTRYGUN") (weapon "knife") (objectowner "Engie<5><[U:1:55]><Red>") (attacker_position "1 2 3")"#;
+        let MessageType::KilledObject {
+            attacker,
+            object,
+            weapon,
+            owner,
+            assister,
+        } = get_message_type(SPY).unwrap().1
+        else {
+            panic!("not a killed object");
+        };
+        assert!(attacker.name == "Spy" && object == BuildingType::Sentry);
+        assert!(weapon.as_deref() == Some("knife"));
+        assert!(owner.is_some_and(|owner| owner.name == "Engie" && owner.uid == 5));
+        assert!(assister.is_none());
+
+        const ASSISTED: &str = r#""Spy<2><[U:1:1]><Blue>" triggered "killedobject" (object "OBJ_SENTRYGUN") (weapon "knife") (objectowner "Engie<5><[U:1:55]><Red>") (assist "Medic<7><[U:1:77]><Blue>")"#;
+        let MessageType::KilledObject { assister, .. } = get_message_type(ASSISTED).unwrap().1
+        else {
+            panic!("not a killed object");
+        };
+        assert!(assister.is_some_and(|assister| assister.name == "Medic" && assister.uid == 7));
+
+        const WORLD: &str = r#""Engie<5><[U:1:55]><Red>" triggered "killedobject" (object "OBJ_DISPENSER") (objectowner "World<0><><>")"#;
+        let MessageType::KilledObject { owner, weapon, .. } = get_message_type(WORLD).unwrap().1
+        else {
+            panic!("not a killed object");
+        };
+        assert!(owner.is_none() && weapon.is_none());
+    }
+
+    #[test]
+    fn flag_positions() {
+        const DROPPED: &str = r#""P<2><[U:1:2]><Blue>" triggered "flagevent" (event "dropped") (position "-1536 2048 -96")"#;
+        let MessageType::FlagEvent {
+            event, position, ..
+        } = get_message_type(DROPPED).unwrap().1
+        else {
+            panic!("not a flag event");
+        };
+        assert!(event == FlagEventKind::Dropped);
+        assert!(
+            position
+                == Some(Position {
+                    x: -1536,
+                    y: 2048,
+                    z: -96
+                })
+        );
+
+        const NO_POSITION: &str =
+            r#""P<2><[U:1:2]><Blue>" triggered "flagevent" (event "captured")"#;
+        let MessageType::FlagEvent {
+            event, position, ..
+        } = get_message_type(NO_POSITION).unwrap().1
+        else {
+            panic!("not a flag event");
+        };
+        assert!(event == FlagEventKind::Captured && position.is_none());
+    }
+
+    #[test]
+    fn insurgency_captured_point() {
+        const LINE: &str =
+            r#""Player<1><[U:1:1]><#Team_One>" triggered "captured_point" (point "B")"#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::CapturedPoint {
+                    user: User {
+                        name: "Player".to_owned(),
+                        uid: 1,
+                        steamid: "[U:1:1]".to_owned(),
+                        team: "#Team_One".to_owned(),
+                    },
+                    point: "B".to_owned(),
+                }
+        );
+    }
+
+    #[test]
+    fn map_status() {
+        let lines = ["Map: koth_highpass", "changelevel koth_highpass"];
+        for line in lines {
+            assert!(
+                get_message_type(line).unwrap().1
+                    == MessageType::MapStatus {
+                        name: "koth_highpass".to_owned(),
+                    }
+            );
+        }
+    }
+
+    #[test]
+    fn unquoted_user_chat() {
+        const LINE: &str = r#"Some <Player><1><[U:1:1]><Red> say "hi""#;
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::ChatMessage {
+                    from: User {
+                        name: "Some <Player>".to_owned(),
+                        uid: 1,
+                        steamid: "[U:1:1]".to_owned(),
+                        team: "Red".to_owned(),
+                    },
+                    message: "hi".to_owned(),
+                    team: false,
+                }
+        );
+        assert!(unquoted_user(r#""Player<1><[U:1:1]><Red>" say "hi""#).is_err());
+    }
+
+    #[test]
+    fn capture_blocked_positions() {
+        let lines = [
+            r##""P<3><[U:1:3]><Blue>" triggered "captureblocked" (cp "1") (cpname "#Gravelpit_cap_B") (position "-10 20 -30")"##,
+            r##""P<3><[U:1:3]><Blue>" [-10 20 -30] triggered "captureblocked" (cp "1") (cpname "#Gravelpit_cap_B")"##,
+        ];
+        for line in lines {
+            let MessageType::CaptureBlocked {
+                user,
+                cp,
+                cpname,
+                position,
+                ..
+            } = get_message_type(line).unwrap().1
+            else {
+                panic!("not a capture block: {line}");
+            };
+            assert!(
+                user == User {
+                    name: "P".to_owned(),
+                    uid: 3,
+                    steamid: "[U:1:3]".to_owned(),
+                    team: "Blue".to_owned(),
+                }
+            );
+            assert!(cp == "1" && cpname == "#Gravelpit_cap_B");
+            assert!(
+                position
+                    == Some(Position {
+                        x: -10,
+                        y: 20,
+                        z: -30
+                    }),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn domination_with_weapon() {
+        const LINE: &str = "\"A<1><[U:1:1]><Red>\" triggered \"domination\" against \"B<2><[U:1:2]><Blue>\" (weapon \"scattergun\")";
+        match get_message_type(LINE).unwrap().1 {
+            MessageType::Domination { weapon, .. } => {
+                assert!(weapon.as_deref() == Some("scattergun"))
+            }
+            _ => panic!("not a domination"),
+        }
+    }
+
+    #[test]
+    fn cs_team_implausible_on_tf2() {
+        const CS_JOIN: &str = "\"A<1><[U:1:1]><Unassigned>\" joined team \"TERRORIST\"";
+        const TF_JOIN: &str = "\"A<1><[U:1:1]><Unassigned>\" joined team \"Red\"";
+        let cs_join = get_message_type(CS_JOIN).unwrap().1;
+        let tf_join = get_message_type(TF_JOIN).unwrap().1;
+        assert!(!is_plausible_for(Game::Tf2, &cs_join));
+        assert!(is_plausible_for(Game::CounterStrike, &cs_join));
+        assert!(is_plausible_for(Game::Tf2, &tf_join));
+        assert!(is_plausible_for(
+            Game::Other("garrysmod".to_owned()),
+            &cs_join
+        ));
+
+        const INSURGENCY: &str =
+            r#""A<1><[U:1:1]><Security>" triggered "captured_point" (point "A")"#;
+        let capture = get_message_type(INSURGENCY).unwrap().1;
+        assert!(capture.kind() == MessageKind::CapturedPoint);
+        assert!(is_plausible_for(
+            Game::Other("insurgency".to_owned()),
+            &capture
+        ));
+        assert!(is_plausible_for(Game::CounterStrike, &capture));
+    }
+
+    #[test]
+    fn crit_damage() {
+        const LINE: &str = r#""A<1><[U:1:1]><Red>" triggered "damage" against "B<2><[U:1:2]><Blue>" (damage "68") (weapon "scattergun") (healing "0") (crit "crit")"#;
+        let MessageType::Damage {
+            attacker,
+            victim,
+            amount,
+            weapon,
+            crit,
+        } = get_message_type(LINE).unwrap().1
+        else {
+            panic!("not damage");
+        };
+        assert!(attacker.name == "A" && victim.name == "B");
+        assert!(amount == 68);
+        assert!(weapon.as_deref() == Some("scattergun"));
+        assert!(crit.as_deref() == Some("crit"));
+    }
+
+    #[test]
+    fn damage_crit_kinds() {
+        const CRIT: &str = r#""A<1><[U:1:1]><Red>" triggered "damage" against "B<2><[U:1:2]><Blue>" (damage "150") (weapon "tf_projectile_rocket") (crit "crit")"#;
+        const MINICRIT: &str = r#""A<1><[U:1:1]><Red>" triggered "damage" against "B<2><[U:1:2]><Blue>" (damage "81") (weapon "tf_projectile_rocket") (crit "minicrit")"#;
+        const NORMAL: &str = r#""A<1><[U:1:1]><Red>" triggered "damage" against "B<2><[U:1:2]><Blue>" (damage "60") (weapon "tf_projectile_rocket")"#;
+        let crit = get_message_type(CRIT).unwrap().1;
+        assert!(crit.crit_kind() == Some(CritKind::Crit) && crit.is_crit());
+        let minicrit = get_message_type(MINICRIT).unwrap().1;
+        assert!(minicrit.crit_kind() == Some(CritKind::MiniCrit) && minicrit.is_crit());
+        let normal = get_message_type(NORMAL).unwrap().1;
+        assert!(normal.crit_kind().is_none() && !normal.is_crit());
+    }
+
+    #[test]
+    fn reservation_cookie() {
+        const LINE: &str =
+            "-> Reservation cookie 1d5c0a5e8b2f4e01:  reason [R] Connect from 10.0.0.5:27005";
+        assert!(
+            get_message_type(LINE).unwrap().1
+                == MessageType::Reservation {
+                    cookie: "1d5c0a5e8b2f4e01".to_owned()
+                }
+        );
+    }
+
+    #[test]
+    fn chat_with_quotes() {
+        const LINE: &str = r#""A<1><[U:1:1]><Red>" say "he said "gg" and left""#;
+        let (rest, parsed) = get_message_type(LINE).unwrap();
+        assert!(rest.is_empty());
+        assert!(
+            parsed
+                == MessageType::ChatMessage {
+                    from: User {
+                        name: "A".to_owned(),
+                        uid: 1,
+                        steamid: "[U:1:1]".to_owned(),
+                        team: "Red".to_owned(),
+                    },
+                    message: r#"he said "gg" and left"#.to_owned(),
+                    team: false,
+                }
+        );
+    }
+
+    #[test]
+    fn player_position_heartbeat() {
+        const LINE: &str = r#""P<1><[U:1:1]><Red>" position "100 200 30" angles "0 90.5 0""#;
+        let MessageType::PlayerPosition {
+            user,
+            position,
+            angles,
+        } = get_message_type(LINE).unwrap().1
+        else {
+            panic!("not a position");
+        };
+        assert!(user.name == "P");
+        assert!(
+            position
+                == Position {
+                    x: 100,
+                    y: 200,
+                    z: 30
+                }
+        );
+        assert!(
+            angles
+                == Some(Angles {
+                    pitch: 0.0,
+                    yaw: 90.5,
+                    roll: 0.0
+                })
+        );
+
+        const NO_ANGLES: &str = r#""P<1><[U:1:1]><Red>" position "-5 0 7""#;
+        let MessageType::PlayerPosition { angles, .. } = get_message_type(NO_ANGLES).unwrap().1
+        else {
+            panic!("not a position");
+        };
+        assert!(angles.is_none());
+    }
+
+    #[test]
+    fn engine_diagnostics() {
+        const FRAME: &str = "Server frame took 84.21 ms (tick 1024)";
+        assert!(
+            get_message_type(FRAME).unwrap().1
+                == MessageType::Diagnostic {
+                    text: FRAME.to_owned()
+                }
+        );
+        const FPS: &str = "FPS drop detected: 33.0 fps";
+        assert!(get_message_type(FPS).unwrap().1.kind() == MessageKind::Diagnostic);
+    }
+
+    #[test]
+    fn state_changes() {
+        const KILL: &str = r#""A<1><[U:1:1]><Red>" killed "B<2><[U:1:2]><Blue>" with "scattergun""#;
+        const CHAT: &str = r#""A<1><[U:1:1]><Red>" say "nice""#;
+        assert!(get_message_type(KILL).unwrap().1.is_state_change());
+        assert!(!get_message_type(CHAT).unwrap().1.is_state_change());
+    }
+
+    #[test]
+    fn kv_pair_windows_paths() {
+        assert!(
+            kv_pair(r#"(file "logs\L0209001.log")"#) == Ok(("", ("file", r"logs\L0209001.log")))
+        );
+        assert!(kv_pair(r#"(dir "C:\srcds\tf\")"#) == Ok(("", ("dir", r"C:\srcds\tf\"))));
+        assert!(kv_pair(r#"(game"tf") (x "1")"#) == Ok((r#" (x "1")"#, ("game", "tf"))));
+
+        const LINE: &str = r#"Log file started (file "logs\L0209001.log") (game "C:\srcds\tf") (version "8622567")"#;
+        let MessageType::LogFileStarted { file, game, .. } = get_message_type(LINE).unwrap().1
+        else {
+            panic!("not a log file start");
+        };
+        assert!(file == r"logs\L0209001.log" && game == r"C:\srcds\tf");
+    }
+
+    #[test]
+    fn player_spawn_class_and_team() {
+        const LINE: &str = r#""P<1><[U:1:1]><Unassigned>" triggered "player_spawn" (class "heavyweapons") (team "Blue")"#;
+        let MessageType::PlayerSpawn { user, class, team } = get_message_type(LINE).unwrap().1
+        else {
+            panic!("not a spawn");
+        };
+        assert!(user.name == "P");
+        assert!(class == TfClass::Heavy);
+        assert!(team == Team::Blue);
+
+        const NO_TEAM: &str = r#""P<1><[U:1:1]><Red>" triggered "player_spawn" (class "scout")"#;
+        let MessageType::PlayerSpawn { class, team, .. } = get_message_type(NO_TEAM).unwrap().1
+        else {
+            panic!("not a spawn");
+        };
+        assert!(class == TfClass::Scout && team == Team::Red);
+    }
+
+    #[test]
+    fn loading_map_spawnflags() {
+        const LINE: &str = r#"Loading map "cp_process_final" (spawnflags "0")"#;
+        let (rest, parsed) = get_message_type(LINE).unwrap();
+        assert!(rest.is_empty());
+        assert!(
+            parsed
+                == MessageType::LoadingMap {
+                    name: "cp_process_final".to_owned(),
+                    properties: Properties(vec![("spawnflags".to_owned(), "0".to_owned())]),
+                }
+        );
+    }
+
+    #[test]
+    fn fatal_and_engine_errors() {
+        const HOST_ERROR: &str = "Host_Error: Couldn't allocate any edicts";
+        assert!(get_message_type(HOST_ERROR).unwrap().1.kind() == MessageKind::FatalError);
+        const SEGFAULT: &str = "Segmentation fault (core dumped)";
+        assert!(
+            get_message_type(SEGFAULT).unwrap().1
+                == MessageType::FatalError {
+                    text: SEGFAULT.to_owned()
+                }
+        );
+
+        const WARNING: &str = "Warning: Unable to load model models/props/crate.mdl";
+        assert!(
+            get_message_type(WARNING).unwrap().1
+                == MessageType::EngineError {
+                    text: "Unable to load model models/props/crate.mdl".to_owned()
                 }
         );
+
+        const ASSERT: &str = "Assertion Failed: pEntity != NULL";
+        assert!(get_message_type(ASSERT).unwrap().1.kind() == MessageKind::FatalError);
+        const NOT_ASSERT: &str = "asserting dominance over the server";
+        assert!(get_message_type(NOT_ASSERT).is_err());
+    }
+
+    #[test]
+    fn typed_property_getters() {
+        const LINE: &str = r#""M<1><[U:1:1]><Red>" triggered "first_heal_after_spawn" (time "2.5") (count "3") (position "1 -2 3")"#;
+        let MessageType::PlayerTriggered { properties, .. } = get_message_type(LINE).unwrap().1
+        else {
+            panic!("not a player trigger");
+        };
+        assert!(properties.get("time") == Some("2.5"));
+        assert!(properties.get_u32("count") == Some(3));
+        assert!(properties.get_u32("time").is_none());
+        assert!(properties.get_f32("time") == Some(2.5));
+        assert!(properties.get_position("position") == Some(Position { x: 1, y: -2, z: 3 }));
+        assert!(properties.get("missing").is_none() && properties.get_u32("missing").is_none());
+        assert!(properties.get_f32("missing").is_none());
+        assert!(properties.get_position("missing").is_none());
+
+        let keys: Vec<_> = properties.into_iter().map(|(key, _)| key).collect();
+        assert!(keys == ["time", "count", "position"]);
+    }
+
+    #[test]
+    fn l4d2_incapacitated_and_revived() {
+        const INCAP: &str = r#""Survivor<1><[U:1:1]><Survivor>" triggered "incapacitated" against "Hunter<2><[U:1:2]><Infected>""#;
+        let MessageType::Incapacitated { victim, attacker } = get_message_type(INCAP).unwrap().1
+        else {
+            panic!("not an incapacitation");
+        };
+        assert!(victim.name == "Survivor" && attacker.name == "Hunter");
+
+        const REVIVED: &str = r#""Medic<1><[U:1:1]><Survivor>" triggered "revived" against "Downed<2><[U:1:2]><Survivor>""#;
+        let revived = get_message_type(REVIVED).unwrap().1;
+        let MessageType::Revived {
+            reviver,
+            revived: downed,
+        } = &revived
+        else {
+            panic!("not a revive");
+        };
+        assert!(reviver.name == "Medic" && downed.name == "Downed");
+        assert!(is_plausible_for(Game::L4d2, &revived));
+        assert!(!is_plausible_for(Game::Tf2, &revived));
+    }
+
+    #[test]
+    fn payload_objectives() {
+        const CAPTURE: &str = r##"Team "Blue" triggered "pointcaptured" (cp "cp_stage1_last") (cpname "#Badwater_cap_4") (numcappers "1") (player1 "P<1><[U:1:1]><Blue>") (position1 "1 2 3") (capturedist "1536.5")"##;
+        let parsed = get_message_type(CAPTURE).unwrap().1;
+        assert!(parsed.capture() == Some(("cp_stage1_last", "#Badwater_cap_4")));
+        assert!(parsed.objective_players().len() == 1);
+        let MessageType::TeamTriggered { properties, .. } = parsed else {
+            panic!("not a team trigger");
+        };
+        assert!(properties.get_f32("capturedist") == Some(1536.5));
+
+        const BLOCK: &str = r##""P<2><[U:1:2]><Red>" triggered "captureblocked" (cp "cart") (cpname "#Badwater_cap_4") (position "4 5 6") (capturedist "120")"##;
+        let MessageType::CaptureBlocked { cp, properties, .. } = get_message_type(BLOCK).unwrap().1
+        else {
+            panic!("not a capture block");
+        };
+        assert!(cp == "cart");
+        assert!(properties.get_u32("capturedist") == Some(120));
+    }
+
+    #[test]
+    fn server_shutdown_lines() {
+        for line in ["Server shutting down", "quit"] {
+            assert!(get_message_type(line).unwrap().1 == MessageType::ServerShutdown);
+        }
+        assert!(get_message_type("quitting soon").is_err());
     }
 }
@@ -0,0 +1,97 @@
+use crate::{MessageType, User};
+use chrono::{Duration, NaiveDateTime};
+use std::collections::HashMap;
+
+/// A player connected again shortly after disconnecting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RapidReconnect {
+    /// The user as seen on the reconnect
+    pub user: User,
+    /// Time between the disconnect and the reconnect
+    pub gap: Duration,
+}
+
+/// Flags players that reconnect within a time window of disconnecting, keyed by
+/// [`User::identity_key`] so bots, which all share the `BOT` steamid, are told apart.
+///
+/// Feed it every event of a log in timestamp order.
+#[derive(Debug, Clone)]
+pub struct ReconnectDetector {
+    window: Duration,
+    disconnects: HashMap<String, NaiveDateTime>,
+}
+
+impl ReconnectDetector {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            disconnects: HashMap::new(),
+        }
+    }
+
+    /// Processes one event, returning a [`RapidReconnect`] if it is a connect that followed
+    /// a disconnect of the same player within the window
+    pub fn feed(
+        &mut self,
+        timestamp: NaiveDateTime,
+        message: &MessageType,
+    ) -> Option<RapidReconnect> {
+        match message {
+            MessageType::Disconnected { user, .. } => {
+                self.disconnects.insert(user.identity_key(), timestamp);
+                None
+            }
+            MessageType::Connected { user, .. } => {
+                let disconnected_at = self.disconnects.remove(&user.identity_key())?;
+                let gap = timestamp - disconnected_at;
+                (gap <= self.window).then(|| RapidReconnect {
+                    user: user.clone(),
+                    gap,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogMessage;
+
+    #[test]
+    fn quick_reconnect() {
+        const LINES: [&str; 4] = [
+            "L 02/09/2024 - 08:00:00: \"User<1><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"",
+            "L 02/09/2024 - 08:10:00: \"User<1><[U:1:123456789]><Red>\" disconnected (reason \"Disconnect by user.\")",
+            "L 02/09/2024 - 08:10:05: \"Other<2><[U:1:987654321]><>\" connected, address \"192.168.0.2:27005\"",
+            "L 02/09/2024 - 08:10:20: \"Alt<3><[U:1:123456789]><>\" connected, address \"192.168.0.1:27005\"",
+        ];
+        let mut detector = ReconnectDetector::new(Duration::seconds(30));
+        let flagged: Vec<RapidReconnect> = LINES
+            .iter()
+            .map(|l| l.parse::<LogMessage>().unwrap())
+            .filter_map(|m| detector.feed(m.timestamp, &m.parse_message_type()))
+            .collect();
+
+        assert!(flagged.len() == 1);
+        assert!(flagged[0].user.name == "Alt");
+        assert!(flagged[0].gap == Duration::seconds(20));
+    }
+
+    #[test]
+    fn different_bots_not_flagged() {
+        const LINES: [&str; 2] = [
+            "L 02/09/2024 - 08:10:00: \"Bot A<3><BOT><Red>\" disconnected (reason \"Kicked from server\")",
+            "L 02/09/2024 - 08:10:05: \"Bot B<4><BOT><>\" connected, address \"none\"",
+        ];
+        let mut detector = ReconnectDetector::new(Duration::seconds(30));
+        let flagged = LINES
+            .iter()
+            .map(|l| l.parse::<LogMessage>().unwrap())
+            .filter_map(|m| detector.feed(m.timestamp, &m.parse_message_type()))
+            .count();
+
+        assert!(flagged == 0);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{LogMessage, MessageType};
+use chrono::NaiveDateTime;
+use std::iter::Peekable;
+
+/// The events of a single round, from its `Round_Start` to its `Round_Win` or `Game_Over`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    /// When `Round_Start` was logged
+    pub start: NaiveDateTime,
+    /// When the round was won or the game ended, or the last event if neither was logged
+    pub end: NaiveDateTime,
+    /// Every event of the round, including the ones starting and ending it
+    pub events: Vec<LogMessage>,
+}
+
+/// Splits a time-sorted stream of messages into [`Round`]s on the `Round_Start`, `Round_Win`
+/// and `Game_Over` world triggers.
+///
+/// Events between rounds, like those before the first `Round_Start` or during humiliation after
+/// a win, belong to no round and are skipped. A round that is never won ends at the next
+/// `Round_Start` or at the end of the stream.
+pub struct RoundSplitter<I: Iterator<Item = LogMessage>> {
+    messages: Peekable<I>,
+}
+
+impl<I: Iterator<Item = LogMessage>> RoundSplitter<I> {
+    pub fn new(messages: I) -> Self {
+        Self {
+            messages: messages.peekable(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = LogMessage>> Iterator for RoundSplitter<I> {
+    type Item = Round;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = loop {
+            let msg = self.messages.next()?;
+            if world_action(&msg).as_deref() == Some("Round_Start") {
+                break msg;
+            }
+        };
+
+        let mut round = Round {
+            start: start.timestamp,
+            end: start.timestamp,
+            events: vec![start],
+        };
+        while let Some(action) = self.messages.peek().map(world_action) {
+            if action.as_deref() == Some("Round_Start") {
+                break;
+            }
+            let msg = self.messages.next()?;
+            round.end = msg.timestamp;
+            round.events.push(msg);
+            if matches!(action.as_deref(), Some("Round_Win" | "Game_Over")) {
+                break;
+            }
+        }
+        Some(round)
+    }
+}
+
+/// The action of a `World triggered` message
+fn world_action(msg: &LogMessage) -> Option<String> {
+    match msg.parse_message_type() {
+        MessageType::WorldTriggered { action, .. } => Some(action),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn two_round_match() {
+        let lines = [
+            "L 02/09/2024 - 08:00:00: \"A<1><[U:1:1]><Red>\" say \"warmup\"",
+            "L 02/09/2024 - 08:00:10: World triggered \"Round_Start\"",
+            "L 02/09/2024 - 08:01:00: \"A<1><[U:1:1]><Red>\" killed \"B<2><[U:1:2]><Blue>\" with \"scattergun\"",
+            "L 02/09/2024 - 08:02:00: World triggered \"Round_Win\" (winner \"Red\")",
+            "L 02/09/2024 - 08:02:05: \"A<1><[U:1:1]><Red>\" say \"gg\"",
+            "L 02/09/2024 - 08:02:20: World triggered \"Round_Start\"",
+            "L 02/09/2024 - 08:03:00: \"B<2><[U:1:2]><Blue>\" killed \"A<1><[U:1:1]><Red>\" with \"minigun\"",
+            "L 02/09/2024 - 08:04:00: World triggered \"Game_Over\" reason \"Reached Time Limit\"",
+        ];
+        let messages = lines.map(|line| LogMessage::from_str(line).unwrap());
+        let rounds: Vec<_> = RoundSplitter::new(messages.clone().into_iter()).collect();
+        assert!(rounds.len() == 2);
+
+        assert!(rounds[0].start == messages[1].timestamp);
+        assert!(rounds[0].end == messages[3].timestamp);
+        assert!(rounds[0].events == messages[1..4]);
+
+        assert!(rounds[1].start == messages[5].timestamp);
+        assert!(rounds[1].end == messages[7].timestamp);
+        assert!(rounds[1].events == messages[5..]);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{LogMessage, MessageType, User};
+use chrono::{Duration, NaiveDateTime};
+use std::collections::HashMap;
+
+/// Tallies `(kills, deaths)` per [`User::identity_key`], the steamid of a player, from the
+/// `Killed` and `Suicide` events of a log.
+///
+/// Killing yourself counts as a death but not a kill.
+pub fn scoreboard(msgs: &[LogMessage]) -> HashMap<String, (u32, u32)> {
+    let mut tallies: HashMap<String, (u32, u32)> = HashMap::new();
+    for msg in msgs {
+        match msg.parse_message_type() {
+            MessageType::Killed {
+                attacker, victim, ..
+            } => {
+                let (attacker, victim) = (attacker.identity_key(), victim.identity_key());
+                if attacker != victim {
+                    tallies.entry(attacker).or_default().0 += 1;
+                }
+                tallies.entry(victim).or_default().1 += 1;
+            }
+            MessageType::Suicide { user, .. } => {
+                tallies.entry(user.identity_key()).or_default().1 += 1;
+            }
+            _ => (),
+        }
+    }
+    tallies
+}
+
+/// A player's running totals in a [`Scoreboard`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerTally {
+    /// The name the player was last seen with
+    pub name: String,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    /// Health healed onto teammates, from `healed` triggers
+    pub healing: u32,
+    /// Control points captured, counting every capper of a `pointcaptured`, whether listed by
+    /// the team trigger or logging it themselves
+    pub captures: u32,
+}
+
+/// Per-player tallies built up from a stream of events, one [`MessageType`] at a time.
+///
+/// Players are keyed on [`User::identity_key`], their steamid, so their totals carry over
+/// disconnects and reconnects. Killing yourself counts as a death but not a kill, like
+/// [`scoreboard`].
+#[derive(Debug, Clone, Default)]
+pub struct Scoreboard {
+    players: HashMap<String, PlayerTally>,
+}
+
+impl Scoreboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single event to the tallies, ignoring any that don't affect them
+    pub fn push(&mut self, msg: &MessageType) {
+        match msg {
+            MessageType::Killed {
+                attacker,
+                victim,
+                assister,
+                ..
+            } => {
+                if attacker.identity_key() != victim.identity_key() {
+                    self.tally(attacker).kills += 1;
+                }
+                self.tally(victim).deaths += 1;
+                if let Some(assister) = assister {
+                    self.tally(assister).assists += 1;
+                }
+            }
+            MessageType::Suicide { user, .. } => self.tally(user).deaths += 1,
+            MessageType::InterPlayerAction {
+                from,
+                action,
+                properties,
+                ..
+            } if action == "healed" => {
+                if let Some(healing) = properties.get_u32("healing") {
+                    self.tally(from).healing += healing;
+                }
+            }
+            MessageType::CapturedPoint { user, .. } => self.tally(user).captures += 1,
+            _ if msg.capture().is_some() => {
+                // the team form lists its cappers, the per-player form is logged by the capper
+                let mut cappers: Vec<User> = msg
+                    .objective_players()
+                    .into_iter()
+                    .map(|(user, _)| user)
+                    .collect();
+                if let MessageType::PlayerTriggered { user, .. } = msg {
+                    cappers.push(user.clone());
+                }
+                let mut credited = vec![];
+                for user in cappers {
+                    let key = user.identity_key();
+                    if !credited.contains(&key) {
+                        self.tally(&user).captures += 1;
+                        credited.push(key);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The tallies of the player with `steamid`, or `BOT:<uid>` for a bot
+    pub fn player(&self, steamid: &str) -> Option<&PlayerTally> {
+        self.players.get(steamid)
+    }
+
+    /// Every player's `(identity key, tally)`, most kills first and ties broken by key
+    pub fn top_killers(&self) -> Vec<(&str, &PlayerTally)> {
+        let mut players: Vec<_> = self
+            .players
+            .iter()
+            .map(|(steamid, tally)| (steamid.as_str(), tally))
+            .collect();
+        players.sort_by(|(a_id, a), (b_id, b)| b.kills.cmp(&a.kills).then(a_id.cmp(b_id)));
+        players
+    }
+
+    /// Kills per death of the player with `steamid`, or their kills if they never died
+    pub fn kd_ratio(&self, steamid: &str) -> Option<f32> {
+        let tally = self.player(steamid)?;
+        Some(tally.kills as f32 / tally.deaths.max(1) as f32)
+    }
+
+    fn tally(&mut self, user: &User) -> &mut PlayerTally {
+        let tally = self.players.entry(user.identity_key()).or_default();
+        tally.name.clone_from(&user.name);
+        tally
+    }
+}
+
+impl<'a> Extend<&'a MessageType> for Scoreboard {
+    fn extend<T: IntoIterator<Item = &'a MessageType>>(&mut self, iter: T) {
+        for msg in iter {
+            self.push(msg);
+        }
+    }
+}
+
+/// A player's time in the server, from connecting to disconnecting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// The user as seen on the connect
+    pub user: User,
+    pub connected_at: NaiveDateTime,
+    /// `None` if the player was still connected when the log ended
+    pub disconnected_at: Option<NaiveDateTime>,
+    pub duration: Option<Duration>,
+}
+
+/// Pairs each `Connected` with the next `Disconnected` of the same player, by
+/// [`User::identity_key`], in timestamp order.
+///
+/// Disconnects of players who connected before the log started are ignored, and a second
+/// connect without a disconnect in between leaves the earlier session open.
+pub fn sessions(msgs: &[LogMessage]) -> Vec<Session> {
+    let mut sessions: Vec<Session> = vec![];
+    let mut open: HashMap<String, usize> = HashMap::new();
+    for msg in msgs {
+        match msg.parse_message_type() {
+            MessageType::Connected { user, .. } => {
+                open.insert(user.identity_key(), sessions.len());
+                sessions.push(Session {
+                    user,
+                    connected_at: msg.timestamp,
+                    disconnected_at: None,
+                    duration: None,
+                });
+            }
+            MessageType::Disconnected { user, .. } => {
+                let key = user.identity_key();
+                if let Some(session) = open.remove(&key).map(|idx| &mut sessions[idx]) {
+                    session.disconnected_at = Some(msg.timestamp);
+                    session.duration = Some(msg.timestamp - session.connected_at);
+                }
+            }
+            _ => (),
+        }
+    }
+    sessions
+}
+
+/// The maps played, in order, from the `Loading map` and `Started map` lines of a log.
+///
+/// Consecutive repeats, like a map's own loading and starting lines, are listed once.
+pub fn map_rotation(msgs: &[LogMessage]) -> Vec<String> {
+    let mut maps: Vec<String> = vec![];
+    for msg in msgs {
+        let (MessageType::LoadingMap { name, .. } | MessageType::StartedMap { name, .. }) =
+            msg.parse_message_type()
+        else {
+            continue;
+        };
+        if maps.last() != Some(&name) {
+            maps.push(name);
+        }
+    }
+    maps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_tallies() {
+        const LINES: [&str; 5] = [
+            "L 02/09/2024 - 08:00:00: \"A<1><[U:1:1]><Red>\" killed \"B<2><[U:1:2]><Blue>\" with \"scattergun\"",
+            "L 02/09/2024 - 08:00:10: \"A<1><[U:1:1]><Red>\" killed \"B<2><[U:1:2]><Blue>\" with \"scattergun\"",
+            "L 02/09/2024 - 08:00:20: \"B<2><[U:1:2]><Blue>\" killed \"A<1><[U:1:1]><Red>\" with \"rocketlauncher\"",
+            "L 02/09/2024 - 08:00:30: \"B<2><[U:1:2]><Blue>\" committed suicide with \"world\"",
+            "L 02/09/2024 - 08:00:40: \"A<1><[U:1:1]><Red>\" say \"gg\"",
+        ];
+        let msgs: Vec<LogMessage> = LINES.iter().map(|l| l.parse().unwrap()).collect();
+        let tallies = scoreboard(&msgs);
+        assert!(tallies.len() == 2);
+        assert!(tallies["[U:1:1]"] == (2, 1));
+        assert!(tallies["[U:1:2]"] == (1, 3));
+    }
+
+    #[test]
+    fn connect_disconnect_sessions() {
+        const LINES: [&str; 4] = [
+            "L 02/09/2024 - 08:00:00: \"A<1><[U:1:1]><>\" connected, address \"1.2.3.4:27005\"",
+            "L 02/09/2024 - 08:00:05: \"B<2><[U:1:2]><>\" connected, address \"1.2.3.5:27005\"",
+            "L 02/09/2024 - 08:30:00: \"A<1><[U:1:1]><Red>\" disconnected (reason \"Disconnect by user.\")",
+            "L 02/09/2024 - 08:31:00: \"C<3><[U:1:3]><Red>\" disconnected (reason \"Disconnect by user.\")",
+        ];
+        let msgs: Vec<LogMessage> = LINES.iter().map(|l| l.parse().unwrap()).collect();
+        let sessions = sessions(&msgs);
+        assert!(sessions.len() == 2);
+
+        assert!(sessions[0].user.name == "A");
+        assert!(sessions[0].disconnected_at == Some(msgs[2].timestamp));
+        assert!(sessions[0].duration == Some(Duration::minutes(30)));
+
+        assert!(sessions[1].user.name == "B");
+        assert!(sessions[1].connected_at == msgs[1].timestamp);
+        assert!(sessions[1].disconnected_at.is_none() && sessions[1].duration.is_none());
+    }
+
+    #[test]
+    fn scripted_match_scoreboard() {
+        let lines = [
+            r#""A<1><[U:1:1]><Red>" killed "B<2><[U:1:2]><Blue>" with "scattergun" (assist "M<3><[U:1:3]><Red>")"#,
+            r#""M<3><[U:1:3]><Red>" triggered "healed" against "A<1><[U:1:1]><Red>" (healing "75")"#,
+            r#""M<3><[U:1:3]><Red>" triggered "healed" against "A<1><[U:1:1]><Red>" (healing "25")"#,
+            r#""B<2><[U:1:2]><Blue>" disconnected (reason "Disconnect by user.")"#,
+            r#""B2<4><[U:1:2]><Blue>" killed "A<1><[U:1:1]><Red>" with "minigun""#,
+            r#""A<1><[U:1:1]><Red>" committed suicide with "world""#,
+            r#"Team "Red" triggered "pointcaptured" (cp "0") (cpname "Mid") (numcappers "2") (player1 "A<1><[U:1:1]><Red>") (position1 "1 2 3") (player2 "M<3><[U:1:3]><Red>") (position2 "4 5 6")"#,
+            r#""A<1><[U:1:1]><Red>" killed "B2<4><[U:1:2]><Blue>" with "scattergun""#,
+        ];
+        let mut board = Scoreboard::new();
+        board.extend(&lines.map(MessageType::from_message));
+
+        let a = board.player("[U:1:1]").unwrap();
+        assert!((a.kills, a.deaths, a.captures) == (2, 2, 1));
+        let b = board.player("[U:1:2]").unwrap();
+        assert!((b.name.as_str(), b.kills, b.deaths) == ("B2", 1, 2));
+        let m = board.player("[U:1:3]").unwrap();
+        assert!((m.assists, m.healing, m.captures) == (1, 100, 1));
+
+        let top: Vec<_> = board.top_killers().iter().map(|(id, _)| *id).collect();
+        assert!(top == ["[U:1:1]", "[U:1:2]", "[U:1:3]"]);
+        assert!(board.kd_ratio("[U:1:2]") == Some(0.5));
+        assert!(board.kd_ratio("[U:1:3]") == Some(0.0));
+        assert!(board.kd_ratio("[U:1:9]").is_none());
+    }
+
+    #[test]
+    fn three_map_rotation() {
+        const LINES: [&str; 7] = [
+            "L 02/09/2024 - 08:00:00: Loading map \"cp_process_final\"",
+            "L 02/09/2024 - 08:00:05: Started map \"cp_process_final\" (CRC \"1\")",
+            "L 02/09/2024 - 08:30:00: Loading map \"cp_gullywash_final1\"",
+            "L 02/09/2024 - 08:30:05: Started map \"cp_gullywash_final1\" (CRC \"2\")",
+            "L 02/09/2024 - 08:30:10: \"A<1><[U:1:1]><Red>\" say \"gl\"",
+            "L 02/09/2024 - 09:00:00: Loading map \"koth_product_final\"",
+            "L 02/09/2024 - 09:00:05: Started map \"koth_product_final\" (CRC \"3\")",
+        ];
+        let msgs: Vec<LogMessage> = LINES.iter().map(|l| l.parse().unwrap()).collect();
+        assert!(
+            map_rotation(&msgs)
+                == [
+                    "cp_process_final",
+                    "cp_gullywash_final1",
+                    "koth_product_final"
+                ]
+        );
+    }
+
+    #[test]
+    fn bots_kept_apart() {
+        const LINES: [&str; 4] = [
+            "L 02/09/2024 - 08:00:00: \"Bot A<2><BOT><>\" connected, address \"none\"",
+            "L 02/09/2024 - 08:00:01: \"Bot B<3><BOT><>\" connected, address \"none\"",
+            "L 02/09/2024 - 08:00:10: \"Bot A<2><BOT><Red>\" killed \"Bot B<3><BOT><Blue>\" with \"scattergun\"",
+            "L 02/09/2024 - 08:00:20: \"Bot A<2><BOT><Red>\" disconnected (reason \"Kicked\")",
+        ];
+        let msgs: Vec<LogMessage> = LINES.iter().map(|l| l.parse().unwrap()).collect();
+        assert!(msgs[2].parse_message_type().kill_delta().unwrap().1 == 1);
+
+        let tallies = scoreboard(&msgs);
+        assert!(tallies["BOT:2"] == (1, 0) && tallies["BOT:3"] == (0, 1));
+
+        let mut board = Scoreboard::new();
+        board.extend(
+            &msgs
+                .iter()
+                .map(LogMessage::parse_message_type)
+                .collect::<Vec<_>>(),
+        );
+        assert!(board.player("BOT:2").unwrap().kills == 1);
+        assert!(board.player("BOT:3").unwrap().deaths == 1);
+
+        let sessions = sessions(&msgs);
+        assert!(sessions.len() == 2);
+        assert!(sessions[0].user.name == "Bot A" && sessions[0].disconnected_at.is_some());
+        assert!(sessions[1].user.name == "Bot B" && sessions[1].disconnected_at.is_none());
+    }
+
+    #[test]
+    fn capture_forms_credited() {
+        let lines = [
+            r#"Team "Red" triggered "pointcaptured" (cp "0") (cpname "Mid") (numcappers "1") (player1 "A<1><[U:1:1]><Red>") (position1 "1 2 3")"#,
+            r#""B<2><[U:1:2]><Blue>" triggered "pointcaptured" (cp "1") (cpname "Last")"#,
+            r#""C<3><[U:1:3]><Blue>" triggered "pointcaptured" (cp "1") (cpname "Last") (player1 "C<3><[U:1:3]><Blue>")"#,
+        ];
+        let mut board = Scoreboard::new();
+        board.extend(&lines.map(MessageType::from_message));
+        assert!(board.player("[U:1:1]").unwrap().captures == 1);
+        assert!(board.player("[U:1:2]").unwrap().captures == 1);
+        assert!(board.player("[U:1:3]").unwrap().captures == 1);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{MessageKind, MessageType};
+
+/// Handles each category of [`MessageType`], so consumers can implement only the categories
+/// they care about instead of matching on every variant. See [`MessageType::accept`].
+///
+/// The categories are the same groups [`ParserConfig`](crate::ParserConfig) selects between.
+/// Every method does nothing by default.
+pub trait MessageVisitor {
+    /// Log files, cvars, maps, rcon and other server status lines
+    fn visit_server(&mut self, _message: &MessageType) {}
+    /// `say` / `say_team`
+    fn visit_chat(&mut self, _message: &MessageType) {}
+    /// Connects, disconnects and team changes
+    fn visit_connection(&mut self, _message: &MessageType) {}
+    /// Kills, damage, CS attacks, suicides, dominations and revenges
+    fn visit_kill(&mut self, _message: &MessageType) {}
+    /// Player, team, world and player-against-player triggers, and position heartbeats
+    fn visit_trigger(&mut self, _message: &MessageType) {}
+    /// Team scores, match status, accolades and money changes
+    fn visit_score(&mut self, _message: &MessageType) {}
+    /// Anything that wasn't recognized
+    fn visit_unknown(&mut self, _message: &MessageType) {}
+}
+
+impl MessageType {
+    /// Calls the method of `visitor` for this message's category
+    pub fn accept(&self, visitor: &mut impl MessageVisitor) {
+        use MessageKind::*;
+        match self.kind() {
+            LogFileStarted | LogFileClosed | ServerCvarsStart | ServerCvar | ServerCvarsEnd
+            | LoadingMap | StartedMap | MapStatus | Rcon | PublicIp | NetworkConfig
+            | ExecConfig | PlayerIndexAssigned | BackendStatus | ServerMessage | BanListChange
+            | FatalError | EngineError | Reservation | Diagnostic | ServerShutdown => {
+                visitor.visit_server(self)
+            }
+            ChatMessage => visitor.visit_chat(self),
+            Connected | Disconnected | JoinedTeam => visitor.visit_connection(self),
+            Killed | CsAttack | Damage | Suicide | Domination | Revenge => visitor.visit_kill(self),
+            InterPlayerAction | PlayerTriggered | TeamTriggered | WorldTriggered | MvmWave
+            | ObjectDetonated | KilledObject | BombEvent | FlagEvent | CapturedPoint
+            | CaptureBlocked | PlayerPosition | PlayerSpawn | Incapacitated | Revived => {
+                visitor.visit_trigger(self)
+            }
+            TeamCurrentScore | TeamFinalScore | MatchStatus | Accolade | MoneyChange => {
+                visitor.visit_score(self)
+            }
+            Unknown => visitor.visit_unknown(self),
+        }
+    }
+
+    /// Converts this message into a domain event of the caller's, if `f` maps it to one
+    pub fn map_into<T, F: Fn(&MessageType) -> Option<T>>(&self, f: F) -> Option<T> {
+        f(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        chats: u32,
+        kills: u32,
+    }
+
+    impl MessageVisitor for Counter {
+        fn visit_chat(&mut self, _message: &MessageType) {
+            self.chats += 1;
+        }
+
+        fn visit_kill(&mut self, _message: &MessageType) {
+            self.kills += 1;
+        }
+    }
+
+    #[test]
+    fn counts_chat_and_kills() {
+        let lines = [
+            r#""A<1><[U:1:1]><Red>" say "hi""#,
+            r#""A<1><[U:1:1]><Red>" say_team "push""#,
+            r#""A<1><[U:1:1]><Red>" killed "B<2><[U:1:2]><Blue>" with "scattergun""#,
+            r#""B<2><[U:1:2]><Blue>" committed suicide with "world""#,
+            r#""B<2><[U:1:2]><Blue>" joined team "Red""#,
+            "Log file closed",
+        ];
+        let mut counter = Counter::default();
+        for line in lines {
+            MessageType::from_message(line).accept(&mut counter);
+        }
+        assert!(counter.chats == 2);
+        assert!(counter.kills == 2);
+
+        let weapon = MessageType::from_message(lines[2]).map_into(|msg| match msg {
+            MessageType::Killed { weapon, .. } => Some(weapon.clone()),
+            _ => None,
+        });
+        assert!(weapon.as_deref() == Some("scattergun"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::LogMessage;
+use chrono::{Duration, NaiveDateTime};
+use std::{collections::BTreeMap, iter::Peekable};
+
+/// Groups time-sorted messages into fixed width buckets, yielding `(bucket_start, messages)`.
+///
+/// Buckets start at the first message's timestamp and follow each other every `width`.
+/// Buckets with no messages in them are skipped rather than yielded empty.
+pub struct TimeWindow<I: Iterator<Item = LogMessage>> {
+    messages: Peekable<I>,
+    width: Duration,
+    origin: Option<NaiveDateTime>,
+}
+
+impl<I: Iterator<Item = LogMessage>> TimeWindow<I> {
+    /// `width` must be at least a millisecond, the resolution buckets are computed in
+    pub fn new(messages: I, width: Duration) -> Self {
+        assert!(
+            width >= Duration::milliseconds(1),
+            "window width must be at least a millisecond"
+        );
+        Self {
+            messages: messages.peekable(),
+            width,
+            origin: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = LogMessage>> Iterator for TimeWindow<I> {
+    type Item = (NaiveDateTime, Vec<LogMessage>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.messages.next()?;
+        let origin = *self.origin.get_or_insert(first.timestamp);
+
+        // the bucket the first message falls into, skipping any empty ones before it
+        let width = self.width.num_milliseconds();
+        let offset = (first.timestamp - origin).num_milliseconds().max(0);
+        let start = origin + Duration::milliseconds(offset / width * width);
+        let end = start + self.width;
+
+        let mut bucket = vec![first];
+        while let Some(msg) = self.messages.next_if(|msg| msg.timestamp < end) {
+            bucket.push(msg);
+        }
+        Some((start, bucket))
+    }
+}
+
+/// The messages timestamped in `[start, end)`, found by binary search.
+///
+/// `msgs` must be sorted by timestamp, otherwise the result is unspecified.
+pub fn events_between(
+    msgs: &[LogMessage],
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> &[LogMessage] {
+    let from = msgs.partition_point(|msg| msg.timestamp < start);
+    let to = msgs.partition_point(|msg| msg.timestamp < end).max(from);
+    &msgs[from..to]
+}
+
+/// Groups messages by timestamp, for range queries with [`BTreeMap::range`].
+///
+/// Timestamps only have second resolution, so messages sharing one keep their original order.
+pub fn index_by_time(msgs: Vec<LogMessage>) -> BTreeMap<NaiveDateTime, Vec<LogMessage>> {
+    let mut index: BTreeMap<NaiveDateTime, Vec<LogMessage>> = BTreeMap::new();
+    for msg in msgs {
+        index.entry(msg.timestamp).or_default().push(msg);
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn one_minute_buckets() {
+        let lines = [
+            "L 02/09/2024 - 08:00:00: Log file started",
+            "L 02/09/2024 - 08:00:30: a",
+            "L 02/09/2024 - 08:00:59: b",
+            "L 02/09/2024 - 08:01:00: c",
+            "L 02/09/2024 - 08:03:10: d",
+        ];
+        let messages = lines.map(|line| LogMessage::from_str(line).unwrap());
+        let buckets: Vec<_> = TimeWindow::new(messages.into_iter(), Duration::minutes(1))
+            .map(|(start, msgs)| (start.format("%H:%M:%S").to_string(), msgs.len()))
+            .collect();
+        assert!(
+            buckets
+                == vec![
+                    ("08:00:00".to_owned(), 3),
+                    ("08:01:00".to_owned(), 1),
+                    ("08:03:00".to_owned(), 1),
+                ]
+        );
+    }
+
+    #[test]
+    fn between_excludes_boundaries() {
+        let lines = [
+            "L 02/09/2024 - 08:00:00: a",
+            "L 02/09/2024 - 08:00:59: b",
+            "L 02/09/2024 - 08:01:00: c",
+            "L 02/09/2024 - 08:01:30: d",
+            "L 02/09/2024 - 08:02:00: e",
+        ];
+        let messages = lines.map(|line| LogMessage::from_str(line).unwrap());
+        let at = |msg: usize| messages[msg].timestamp;
+
+        let middle = events_between(&messages, at(1) + Duration::seconds(1), at(4));
+        let middle: Vec<_> = middle.iter().map(|msg| msg.message.as_str()).collect();
+        assert!(middle == ["c", "d"]);
+
+        assert!(events_between(&messages, at(4), at(0)).is_empty());
+        assert!(events_between(&messages, at(0), at(4) + Duration::seconds(1)).len() == 5);
+    }
+
+    #[test]
+    fn same_second_grouped() {
+        let lines = [
+            "L 02/09/2024 - 08:00:00: a",
+            "L 02/09/2024 - 08:00:01: b",
+            "L 02/09/2024 - 08:00:01: c",
+            "L 02/09/2024 - 08:00:02: d",
+        ];
+        let messages = lines.map(|line| LogMessage::from_str(line).unwrap());
+        let second = messages[1].timestamp;
+        let index = index_by_time(messages.to_vec());
+        assert!(index.len() == 3);
+
+        let grouped: Vec<_> = index[&second]
+            .iter()
+            .map(|msg| msg.message.as_str())
+            .collect();
+        assert!(grouped == ["b", "c"]);
+        assert!(index.range(second..).count() == 2);
+    }
+
+    #[test]
+    fn millisecond_width() {
+        let lines = ["L 02/09/2024 - 08:00:00: a", "L 02/09/2024 - 08:00:01: b"];
+        let messages = lines.map(|line| LogMessage::from_str(line).unwrap());
+        let buckets = TimeWindow::new(messages.into_iter(), Duration::milliseconds(1)).count();
+        assert!(buckets == 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least a millisecond")]
+    fn sub_millisecond_width() {
+        TimeWindow::new(std::iter::empty(), Duration::microseconds(500));
+    }
+}